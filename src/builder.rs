@@ -0,0 +1,114 @@
+use super::{Key, KeyPartsSequence};
+
+/// Low-level key builder with the key sequence prefix pre-written.
+/// Every byte written to the builder becomes a part of the key
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+/// use the_key::*;
+/// define_key_part!(Part1, &[10, 20]);
+/// define_key_seq!(MyKeySeq, [Part1]);
+///
+/// fn main() {
+///   let key_seq = MyKeySeq::new();
+///   let mut builder = key_seq.raw_builder();
+///
+///   builder.write_all(&[30, 40]).unwrap();
+///   builder.extend(vec![50]);
+///
+///   let key = builder.finish();
+///
+///   assert_eq!(key.get_prefix(), &[10, 20]);
+///   assert_eq!(key.get_key(), &[30, 40, 50]);
+/// }
+/// ```
+pub struct RawKeyBuilder<'a, T: KeyPartsSequence> {
+  key: Key<'a, T>,
+}
+
+impl<'a, T: KeyPartsSequence> RawKeyBuilder<'a, T> {
+  pub fn new(prefix: Key<'a, T>) -> Self {
+    Self { key: prefix }
+  }
+
+  /// Appends bytes to the key
+  pub fn push_slice(&mut self, bytes: &[u8]) {
+    self.key.bytes.extend_from_slice(bytes);
+    self.key.key_len += bytes.len();
+  }
+
+  /// Finalizes building returning the key
+  pub fn finish(self) -> Key<'a, T> {
+    self.key
+  }
+}
+
+impl<'a, T: KeyPartsSequence> std::io::Write for RawKeyBuilder<'a, T> {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.push_slice(buf);
+
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+impl<'a, T: KeyPartsSequence> Extend<u8> for RawKeyBuilder<'a, T> {
+  fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+    let len = self.key.bytes.len();
+
+    self.key.bytes.extend(iter);
+    self.key.key_len += self.key.bytes.len() - len;
+  }
+}
+
+/// Key builder with the key sequence prefix pre-written.
+/// Text written to the builder by `write!` becomes a part of the key as UTF-8 bytes
+///
+/// # Example
+/// ```
+/// use std::fmt::Write;
+/// use the_key::*;
+/// define_key_part!(Part1, &[10, 20]);
+/// define_key_seq!(MyKeySeq, [Part1]);
+///
+/// fn main() {
+///   let key_seq = MyKeySeq::new();
+///   let mut builder = key_seq.fmt_builder();
+///   let (kind, id) = ("a", 1);
+///
+///   write!(builder, "{}:{}", kind, id).unwrap();
+///
+///   let key = builder.finish();
+///
+///   assert_eq!(key.get_prefix(), &[10, 20]);
+///   assert_eq!(key.get_key_str(), Ok("a:1"));
+/// }
+/// ```
+pub struct KeyFmtBuilder<'a, T: KeyPartsSequence> {
+  builder: RawKeyBuilder<'a, T>,
+}
+
+impl<'a, T: KeyPartsSequence> KeyFmtBuilder<'a, T> {
+  pub fn new(prefix: Key<'a, T>) -> Self {
+    Self {
+      builder: RawKeyBuilder::new(prefix),
+    }
+  }
+
+  /// Finalizes building returning the key
+  pub fn finish(self) -> Key<'a, T> {
+    self.builder.finish()
+  }
+}
+
+impl<'a, T: KeyPartsSequence> std::fmt::Write for KeyFmtBuilder<'a, T> {
+  fn write_str(&mut self, s: &str) -> std::fmt::Result {
+    self.builder.push_slice(s.as_bytes());
+
+    Ok(())
+  }
+}
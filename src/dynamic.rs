@@ -0,0 +1,144 @@
+use super::{KeyExtensionsItem, KeyPart, KeyPartItem, KeyPartsSequence};
+
+/// Key part whose bytes are known only at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+  key_part_name: &'static str,
+  bytes: Vec<u8>,
+}
+
+impl Part {
+  /// Creates a key part from runtime-computed bytes
+  pub fn new_dynamic(key_part_name: &'static str, bytes: Vec<u8>) -> Self {
+    Self {
+      key_part_name,
+      bytes,
+    }
+  }
+
+  /// Returns key part name
+  pub fn get_name(&self) -> &'static str {
+    self.key_part_name
+  }
+
+  /// Returns key part bytes
+  pub fn get_bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+}
+
+/// Key sequence built from runtime parts. The parts it is constructed from define its layout
+/// like static parts do, see [`get_layout_parts`][KeyPartsSequence::get_layout_parts],
+/// and parts added by `extend*` are extensions written after them.
+/// [`same_layout`][super::same_layout] compares types only, so it doesn't see the parts
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// fn main() {
+///   let key_seq = DynamicSeq::from_parts(vec![
+///     Part::new_dynamic("Env", b"prod".to_vec()),
+///     Part::new_dynamic("Users", vec![11, 11]),
+///   ]);
+///
+///   assert_eq!(
+///     format!("{:?}", key_seq.create_key(&[81])),
+///     "Env[112, 114, 111, 100] -> Users[11, 11] -> Key=[81]",
+///   );
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct DynamicSeq {
+  parts: Vec<KeyExtensionsItem>,
+  extensions: Option<Vec<KeyExtensionsItem>>,
+  len: usize,
+}
+
+impl DynamicSeq {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a key sequence from the parts keeping their order
+  pub fn from_parts<I: IntoIterator<Item = Part>>(parts: I) -> Self {
+    parts.into_iter().fold(Self::new(), DynamicSeq::with_part)
+  }
+
+  /// Creates a key sequence from parts of any types keeping their order
+  pub fn from_dyn_parts(parts: &[Box<dyn KeyPart>]) -> Self {
+    parts
+      .iter()
+      .fold(Self::new(), |seq, part| seq.with_dyn_part(part.as_ref()))
+  }
+
+  /// Appends a part to the layout parts of the key sequence. It goes before any extensions
+  pub fn with_part(mut self, part: Part) -> Self {
+    self.len += part.bytes.len();
+    self.parts.push((part.key_part_name, part.bytes));
+
+    self
+  }
+
+  /// Appends a part of any type to the layout parts of the key sequence. It goes before any extensions
+  pub fn with_dyn_part(self, part: &dyn KeyPart) -> Self {
+    self.with_part(Part::new_dynamic(
+      part.get_name(),
+      part.get_bytes().to_vec(),
+    ))
+  }
+}
+
+impl KeyPartsSequence for DynamicSeq {
+  fn new() -> Self {
+    DynamicSeq::new()
+  }
+
+  fn get_struct() -> Vec<KeyPartItem> {
+    Vec::new()
+  }
+
+  fn get_extensions(&self) -> Option<&[KeyExtensionsItem]> {
+    self.extensions.as_deref()
+  }
+
+  fn get_dynamic_parts(&self) -> &[KeyExtensionsItem] {
+    &self.parts
+  }
+
+  fn extend<B: AsRef<[u8]>>(self, key_part_name: &'static str, bytes: B) -> Self {
+    self.extend_owned(key_part_name, bytes.as_ref().to_vec())
+  }
+
+  fn extend_owned(mut self, key_part_name: &'static str, bytes: Vec<u8>) -> Self {
+    self.len += bytes.len();
+    self
+      .extensions
+      .get_or_insert_with(Vec::new)
+      .push((key_part_name, bytes));
+
+    self
+  }
+
+  fn get_prefix_len(&self) -> usize {
+    self.len
+  }
+
+  fn seq_name(&self) -> &'static str {
+    "DynamicSeq"
+  }
+
+  fn write_prefix<F: FnMut(&[u8])>(&self, mut write: F) {
+    self.parts.iter().for_each(|(_, bytes)| write(bytes));
+
+    if let Some(extensions) = &self.extensions {
+      extensions.iter().for_each(|(_, bytes)| write(bytes));
+    }
+  }
+}
+
+impl std::fmt::Debug for DynamicSeq {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.fmt_debug(&self.get_layout_parts(), self.get_extensions(), f)
+  }
+}
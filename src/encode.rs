@@ -0,0 +1,141 @@
+/// Value which can be written into a key so that lexicographic order of the bytes
+/// is equal to the order of the values.
+///
+/// - unsigned integers are written in big-endian byte order
+/// - signed integers are written in big-endian byte order with the sign bit flipped
+/// - `bool` is written as `0` or `1`
+/// - strings are written with `0x00` bytes escaped as `0x00 0xFF` and terminated by `0x00 0x00`,
+///   so a string goes before any longer string starting with it
+/// - tuples are written field by field
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// fn main() {
+///   let mut buf = Vec::new();
+///   (1u16, -1i8, "ab").encode_ordered(&mut buf);
+///
+///   assert_eq!(buf, vec![0, 1, 0x7F, b'a', b'b', 0, 0]);
+///   assert_eq!((1u16, -1i8, "ab").encoded_len(), buf.len());
+/// }
+/// ```
+pub trait OrderedEncode {
+  /// Appends encoded value to the buffer
+  fn encode_ordered(&self, buf: &mut Vec<u8>);
+
+  /// Returns length of the encoded value in bytes
+  fn encoded_len(&self) -> usize;
+}
+
+macro_rules! impl_ordered_encode_unsigned {
+  ($($ty:ty),*) => {
+    $(
+      impl OrderedEncode for $ty {
+        fn encode_ordered(&self, buf: &mut Vec<u8>) {
+          buf.extend_from_slice(&self.to_be_bytes());
+        }
+
+        fn encoded_len(&self) -> usize {
+          std::mem::size_of::<$ty>()
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_ordered_encode_signed {
+  ($($ty:ty => $unsigned:ty),*) => {
+    $(
+      impl OrderedEncode for $ty {
+        fn encode_ordered(&self, buf: &mut Vec<u8>) {
+          let flipped = (*self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+
+          buf.extend_from_slice(&flipped.to_be_bytes());
+        }
+
+        fn encoded_len(&self) -> usize {
+          std::mem::size_of::<$ty>()
+        }
+      }
+    )*
+  };
+}
+
+impl_ordered_encode_unsigned!(u8, u16, u32, u64, u128);
+impl_ordered_encode_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+impl OrderedEncode for bool {
+  fn encode_ordered(&self, buf: &mut Vec<u8>) {
+    buf.push(*self as u8);
+  }
+
+  fn encoded_len(&self) -> usize {
+    1
+  }
+}
+
+impl OrderedEncode for str {
+  fn encode_ordered(&self, buf: &mut Vec<u8>) {
+    for byte in self.bytes() {
+      buf.push(byte);
+
+      if byte == 0 {
+        buf.push(0xFF);
+      }
+    }
+
+    buf.extend_from_slice(&[0, 0]);
+  }
+
+  fn encoded_len(&self) -> usize {
+    self.len() + self.bytes().filter(|byte| *byte == 0).count() + 2
+  }
+}
+
+impl OrderedEncode for String {
+  fn encode_ordered(&self, buf: &mut Vec<u8>) {
+    self.as_str().encode_ordered(buf);
+  }
+
+  fn encoded_len(&self) -> usize {
+    self.as_str().encoded_len()
+  }
+}
+
+impl<T: OrderedEncode + ?Sized> OrderedEncode for &T {
+  fn encode_ordered(&self, buf: &mut Vec<u8>) {
+    (**self).encode_ordered(buf);
+  }
+
+  fn encoded_len(&self) -> usize {
+    (**self).encoded_len()
+  }
+}
+
+macro_rules! impl_ordered_encode_tuple {
+  ($($name:ident),+) => {
+    impl<$($name: OrderedEncode),+> OrderedEncode for ($($name,)+) {
+      #[allow(non_snake_case)]
+      fn encode_ordered(&self, buf: &mut Vec<u8>) {
+        let ($($name,)+) = self;
+
+        $($name.encode_ordered(buf);)+
+      }
+
+      #[allow(non_snake_case)]
+      fn encoded_len(&self) -> usize {
+        let ($($name,)+) = self;
+
+        0 $(+ $name.encoded_len())+
+      }
+    }
+  };
+}
+
+impl_ordered_encode_tuple!(A);
+impl_ordered_encode_tuple!(A, B);
+impl_ordered_encode_tuple!(A, B, C);
+impl_ordered_encode_tuple!(A, B, C, D);
+impl_ordered_encode_tuple!(A, B, C, D, E);
+impl_ordered_encode_tuple!(A, B, C, D, E, F);
@@ -0,0 +1,49 @@
+/// Error type of fallible key operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+  /// Bytes don't start with the expected prefix
+  PrefixMismatch { expected: Vec<u8>, found: Vec<u8> },
+  /// Bytes are shorter than the minimal required length
+  TooShort { len: usize, min: usize },
+  /// Key is longer than the allowed maximum
+  KeyTooLong { len: usize, max: usize },
+  /// Length of the bytes isn't equal to the expected length
+  LengthMismatch { len: usize, expected: usize },
+  /// Key is empty while the key sequence requires a key
+  EmptyKey,
+}
+
+impl std::fmt::Display for Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Error::PrefixMismatch { expected, found } => {
+        write!(
+          f,
+          "prefix mismatch: expected {:?}, found {:?}",
+          expected, found
+        )
+      }
+      Error::TooShort { len, min } => {
+        write!(
+          f,
+          "bytes are too short: {} bytes, at least {} expected",
+          len, min
+        )
+      }
+      Error::KeyTooLong { len, max } => {
+        write!(f, "key is too long: {} bytes, at most {} allowed", len, max)
+      }
+      Error::LengthMismatch { len, expected } => {
+        write!(
+          f,
+          "length mismatch: {} bytes, exactly {} expected",
+          len, expected
+        )
+      }
+      Error::EmptyKey => write!(f, "key is empty while the key sequence requires a key"),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
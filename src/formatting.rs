@@ -1,57 +1,204 @@
+/// Rendering of bytes in `Debug` output
+#[derive(Clone, Copy)]
+pub enum BytesFormat {
+  /// `[117, 115, 0]`
+  Decimal,
+  /// `["us"\x00]`
+  Ascii,
+}
+
 pub fn format_struct(
-  parts: &[super::KeyPartItem],
+  parts: &[(&'static str, &[u8])],
   extensions: Option<&[super::KeyExtensionsItem]>,
+  separator: &[u8],
   key: Option<(&[u8], usize)>,
+  bytes_format: BytesFormat,
   f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
   let mut prefix_len: usize = 0;
+  let mut i: usize = 0;
 
-  let mut parts = parts
+  let parts = parts.iter().map(|(name, bytes)| (*name, *bytes));
+  let extensions = extensions
+    .unwrap_or_default()
     .iter()
-    .map(|(name, bytes)| {
-      prefix_len += bytes.len();
+    .map(|(name, bytes)| (*name, bytes.as_slice()));
+
+  for (name, bytes) in parts.chain(extensions) {
+    prefix_len += bytes.len();
+
+    write_separator(i, f)?;
+    write!(f, "{}", name)?;
+    write_bytes(bytes, bytes_format, f)?;
 
-      format!("{}{:?}", name, bytes)
-    })
-    .collect::<Vec<String>>();
+    i += 1;
 
-  if let Some(extensions) = extensions {
-    for (name, bytes) in extensions.iter() {
-      prefix_len += bytes.len();
+    if !separator.is_empty() {
+      prefix_len += separator.len();
 
-      parts.push(format!("{}{:?}", name, bytes));
+      write_separator(i, f)?;
+      write_bytes(separator, bytes_format, f)?;
+
+      i += 1;
     }
   }
 
   if let Some(key) = key {
-    parts.push(format!("Key={:?}", &key.0[prefix_len..]));
+    write_separator(i, f)?;
+    write!(f, "Key=")?;
+    write_bytes(&key.0[prefix_len..], bytes_format, f)?;
   }
 
-  if f.alternate() {
-    let mut i: usize = 0;
+  Ok(())
+}
 
-    for part in parts.iter() {
-      let new_line_symbol = match i {
-        0 => "",
-        _ => "\n",
-      };
-      let angle_symbol = match i {
-        0 => "",
-        _ => "└ ",
-      };
-      let padding = " ".repeat(i);
+fn write_bytes(
+  bytes: &[u8],
+  bytes_format: BytesFormat,
+  f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+  match bytes_format {
+    BytesFormat::Decimal => write!(f, "{:?}", bytes),
+    BytesFormat::Ascii => {
+      write!(f, "[")?;
+
+      let mut in_str = false;
 
-      i += 2;
+      for byte in bytes {
+        let printable = (0x20..0x7f).contains(byte);
 
-      let write_result = write!(f, "{}{}{}{}", new_line_symbol, padding, angle_symbol, part);
+        if printable != in_str {
+          write!(f, "\"")?;
+          in_str = printable;
+        }
+
+        match byte {
+          b'"' | b'\\' => write!(f, "\\{}", *byte as char)?,
+          _ if printable => write!(f, "{}", *byte as char)?,
+          _ => write!(f, "\\x{:02x}", byte)?,
+        }
+      }
 
-      if write_result.is_err() {
-        return write_result;
+      if in_str {
+        write!(f, "\"")?;
       }
+
+      write!(f, "]")
     }
-  } else {
-    return write!(f, "{}", parts.join(" -> "));
   }
+}
 
-  Ok(())
+fn write_separator(i: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  match i {
+    0 => Ok(()),
+    _ if f.alternate() => write!(f, "\n{:padding$}└ ", "", padding = i * 2),
+    _ => write!(f, " -> "),
+  }
+}
+
+pub fn format_template(
+  parts: &[(&'static str, &[u8])],
+  extensions: Option<&[super::KeyExtensionsItem]>,
+  separator: &[u8],
+) -> String {
+  let parts = parts.iter().map(|(name, bytes)| (*name, bytes.len()));
+  let extensions = extensions
+    .unwrap_or_default()
+    .iter()
+    .map(|(name, bytes)| (*name, bytes.len()));
+
+  let mut template = String::new();
+
+  for (name, len) in parts.chain(extensions) {
+    push_template_segment(&mut template, name, len);
+
+    if !separator.is_empty() {
+      push_template_segment(&mut template, "sep", separator.len());
+    }
+  }
+
+  template.push_str("<key>");
+
+  template
+}
+
+fn push_template_segment(template: &mut String, name: &str, len: usize) {
+  let unit = if len == 1 { "byte" } else { "bytes" };
+
+  template.push_str(&format!("{}({} {}) | ", name, len, unit));
+}
+
+pub fn format_json(
+  parts: &[(&'static str, &[u8])],
+  extensions: Option<&[super::KeyExtensionsItem]>,
+  separator: &[u8],
+  key: Option<&[u8]>,
+) -> String {
+  let mut json = String::from("{\"parts\":[");
+
+  let parts = parts.iter().map(|(name, bytes)| (*name, *bytes));
+  let extensions = extensions
+    .unwrap_or_default()
+    .iter()
+    .map(|(name, bytes)| (*name, bytes.as_slice()));
+
+  for (i, (name, bytes)) in parts.chain(extensions).enumerate() {
+    if i > 0 {
+      json.push(',');
+    }
+
+    json.push_str("{\"name\":");
+    push_json_str(&mut json, name);
+    json.push_str(",\"bytes\":");
+    push_json_bytes(&mut json, bytes);
+    json.push('}');
+  }
+
+  json.push(']');
+
+  if !separator.is_empty() {
+    json.push_str(",\"separator\":");
+    push_json_bytes(&mut json, separator);
+  }
+
+  if let Some(key) = key {
+    json.push_str(",\"key\":");
+    push_json_bytes(&mut json, key);
+  }
+
+  json.push('}');
+
+  json
+}
+
+fn push_json_str(json: &mut String, value: &str) {
+  json.push('"');
+
+  for c in value.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+      c => json.push(c),
+    }
+  }
+
+  json.push('"');
+}
+
+fn push_json_bytes(json: &mut String, bytes: &[u8]) {
+  json.push('[');
+
+  for (i, byte) in bytes.iter().enumerate() {
+    if i > 0 {
+      json.push(',');
+    }
+
+    json.push_str(&byte.to_string());
+  }
+
+  json.push(']');
 }
@@ -0,0 +1,47 @@
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher. Its output doesn't depend on platform or process
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+  pub(crate) fn new() -> Self {
+    Self(FNV_OFFSET_BASIS)
+  }
+
+  /// Writes length of the bytes as little-endian `u64` followed by the bytes
+  pub(crate) fn write_len_prefixed(&mut self, bytes: &[u8]) {
+    std::hash::Hasher::write(self, &(bytes.len() as u64).to_le_bytes());
+    std::hash::Hasher::write(self, bytes);
+  }
+}
+
+/// Hashes the length-prefixed name and bytes of each part in order followed by the length-prefixed separator
+pub(crate) fn layout_hash<'n, 'b, I: IntoIterator<Item = (&'n str, &'b [u8])>>(
+  parts: I,
+  separator: &[u8],
+) -> u64 {
+  let mut hasher = Fnv1a::new();
+
+  for (name, bytes) in parts {
+    hasher.write_len_prefixed(name.as_bytes());
+    hasher.write_len_prefixed(bytes);
+  }
+
+  hasher.write_len_prefixed(separator);
+
+  std::hash::Hasher::finish(&hasher)
+}
+
+impl std::hash::Hasher for Fnv1a {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for byte in bytes {
+      self.0 ^= *byte as u64;
+      self.0 = self.0.wrapping_mul(FNV_PRIME);
+    }
+  }
+}
@@ -0,0 +1,88 @@
+use super::KeyPartsSequence;
+
+/// Maximal length of a [`the_key::KeyRef`][super::KeyRef] stored without heap allocation
+pub const KEY_REF_INLINE_LEN: usize = 64;
+
+enum KeyRefBytes<'b> {
+  Borrowed(&'b [u8]),
+  Inline([u8; KEY_REF_INLINE_LEN], usize),
+  Heap(Vec<u8>),
+}
+
+/// Read-only key view. Keys up to [`the_key::KEY_REF_INLINE_LEN`][KEY_REF_INLINE_LEN] bytes
+/// are kept on the stack and keys without prefix borrow the key bytes
+pub struct KeyRef<'b> {
+  bytes: KeyRefBytes<'b>,
+  key_len: usize,
+}
+
+impl<'b> KeyRef<'b> {
+  pub(crate) fn from_seq<T: KeyPartsSequence>(seq: &T, key: &'b [u8]) -> Self {
+    let len = seq.get_prefix_len() + key.len();
+
+    let bytes = if len == key.len() {
+      KeyRefBytes::Borrowed(key)
+    } else if len <= KEY_REF_INLINE_LEN {
+      let mut buf = [0; KEY_REF_INLINE_LEN];
+      let mut offset = 0;
+
+      seq.write_prefix(|bytes| {
+        buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+        offset += bytes.len();
+      });
+      buf[offset..len].copy_from_slice(key);
+
+      KeyRefBytes::Inline(buf, len)
+    } else {
+      let mut buf = Vec::with_capacity(len);
+      seq.create_key_into(key, &mut buf);
+
+      KeyRefBytes::Heap(buf)
+    };
+
+    Self {
+      bytes,
+      key_len: key.len(),
+    }
+  }
+
+  /// Returns all key bytes
+  pub fn bytes(&self) -> &[u8] {
+    match &self.bytes {
+      KeyRefBytes::Borrowed(bytes) => bytes,
+      KeyRefBytes::Inline(buf, len) => &buf[..*len],
+      KeyRefBytes::Heap(bytes) => bytes,
+    }
+  }
+
+  /// Returns key bytes
+  pub fn get_key(&self) -> &[u8] {
+    let bytes = self.bytes();
+
+    &bytes[bytes.len() - self.key_len..]
+  }
+
+  /// Returns prefix bytes
+  pub fn get_prefix(&self) -> &[u8] {
+    let bytes = self.bytes();
+
+    &bytes[..bytes.len() - self.key_len]
+  }
+
+  /// Returns `true` if the key bytes are on the heap
+  pub fn is_heap(&self) -> bool {
+    matches!(self.bytes, KeyRefBytes::Heap(_))
+  }
+}
+
+impl<'b> AsRef<[u8]> for KeyRef<'b> {
+  fn as_ref(&self) -> &[u8] {
+    self.bytes()
+  }
+}
+
+impl<'b> std::fmt::Debug for KeyRef<'b> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self.bytes())
+  }
+}
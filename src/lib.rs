@@ -51,24 +51,92 @@
 //! ```
 
 #![feature(test)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 extern crate test;
 
+#[cfg(test)]
+extern crate self as the_key;
+
+mod builder;
+mod dynamic;
+mod encode;
+mod error;
 mod formatting;
+mod hash;
+mod key_ref;
+mod owned;
+pub mod util;
 
-use formatting::format_struct;
+pub use builder::{KeyFmtBuilder, RawKeyBuilder};
+pub use dynamic::{DynamicSeq, Part};
+pub use encode::OrderedEncode;
+pub use error::Error;
+use formatting::{format_json, format_struct, format_template, BytesFormat};
+pub use key_ref::{KeyRef, KEY_REF_INLINE_LEN};
+pub use owned::OwnedKey;
+use std::borrow::Cow;
+use std::convert::{TryFrom, TryInto};
 use std::marker::PhantomData;
 
+/// Derives [`the_key::KeyPart`][KeyPart] for a unit struct. Available with `derive` feature
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// /// Users namespace
+/// #[derive(Debug, Clone, Copy, KeyPart)]
+/// #[key_part(bytes = b"users")]
+/// pub struct Users;
+///
+/// define_key_seq!(UsersSeq, [Users]);
+///
+/// fn main() {
+///   assert_eq!(Users::new().get_name(), "Users");
+///   assert_eq!(UsersSeq::new().create_key(&[1]).to_vec(), b"users\x01".to_vec());
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use the_key_derive::KeyPart;
+
 pub type KeyPartItem = (&'static str, &'static [u8]);
 pub type KeyExtensionsItem = (&'static str, Vec<u8>);
 
+/// Sort order of a key part bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+  /// Bytes are stored as is
+  Ascending,
+  /// Bytes are stored inverted, so bigger values go first.
+  /// Keeps the order only for parts of the same length
+  Descending,
+}
+
+/// Byte order of numbers written into keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+  /// Keeps lexicographic order of the keys equal to numeric order of the numbers
+  #[default]
+  Big,
+  /// Breaks lexicographic order of the keys. Use it only for compatibility with existing data
+  Little,
+}
+
 pub trait KeyPart {
-  fn new() -> Self;
+  fn new() -> Self
+  where
+    Self: Sized;
 
   /// Returns key part name
   fn get_name(&self) -> &'static str;
 
   /// Returns key part bytes
   fn get_bytes(&self) -> &'static [u8];
+
+  /// Returns human description of the key part
+  fn get_description(&self) -> Option<&'static str> {
+    None
+  }
 }
 
 pub trait KeyPartsSequence: Clone {
@@ -76,11 +144,121 @@ pub trait KeyPartsSequence: Clone {
   fn get_struct() -> Vec<KeyPartItem>;
   #[doc(hidden)]
   fn get_extensions(&self) -> Option<&[KeyExtensionsItem]>;
+  #[doc(hidden)]
+  fn get_dynamic_parts(&self) -> &[KeyExtensionsItem] {
+    &[]
+  }
+
+  /// Returns names and bytes of the parts defining the key layout in order: the static parts
+  /// followed by the parts a [`the_key::DynamicSeq`][DynamicSeq] was constructed from. Extensions are not included
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend("Part2", &[30]);
+  ///   let dynamic_seq = DynamicSeq::from_parts(vec![Part::new_dynamic("Part1", vec![10, 20])]);
+  ///
+  ///   assert_eq!(key_seq.get_layout_parts(), vec![("Part1", &[10, 20][..])]);
+  ///   assert_eq!(dynamic_seq.get_layout_parts(), key_seq.get_layout_parts());
+  /// }
+  /// ```
+  fn get_layout_parts(&self) -> Vec<(&'static str, &[u8])> {
+    let dynamic_parts = self.get_dynamic_parts().iter();
+
+    Self::get_struct()
+      .into_iter()
+      .chain(dynamic_parts.map(|(name, bytes)| (*name, bytes.as_slice())))
+      .collect()
+  }
+
+  /// Returns names and bytes of the extensions in the order they were added
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend("Part2", &[30]);
+  ///   let extensions: Vec<(&str, &[u8])> = key_seq.extensions_iter().collect();
+  ///
+  ///   assert_eq!(extensions, vec![("Part2", &[30][..])]);
+  /// }
+  /// ```
+  fn extensions_iter(&self) -> impl Iterator<Item = (&'static str, &[u8])> {
+    self
+      .get_extensions()
+      .unwrap_or_default()
+      .iter()
+      .map(|(name, bytes)| (*name, bytes.as_slice()))
+  }
+
+  /// Returns separator bytes written after each key part
+  fn get_separator() -> &'static [u8] {
+    &[]
+  }
+
+  /// Returns name of the key sequence type. Key sequences defined by
+  /// [`define_key_seq!`][define_key_seq] return the name they were defined with,
+  /// other implementations default to [`std::any::type_name`]
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(MyKeySeq::new().seq_name(), "MyKeySeq");
+  /// }
+  /// ```
+  fn seq_name(&self) -> &'static str {
+    std::any::type_name::<Self>()
+  }
+
+  /// Returns `true` if keys of the sequence must not be empty,
+  /// see [`create_key_checked`][KeyPartsSequence::create_key_checked]
+  fn is_key_required() -> bool {
+    false
+  }
+
+  /// Returns names and descriptions of the layout parts in order
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20], desc = "Registered users");
+  /// define_key_part!(Photos, &[30]);
+  /// define_key_seq!(MyKeySeq, [Users, Photos]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(
+  ///     MyKeySeq::new().describe(),
+  ///     vec![("Users", Some("Registered users")), ("Photos", None)],
+  ///   );
+  /// }
+  /// ```
+  fn describe(&self) -> Vec<(&'static str, Option<&'static str>)> {
+    self
+      .get_layout_parts()
+      .into_iter()
+      .map(|(name, _)| (name, None))
+      .collect()
+  }
 
   fn new() -> Self;
 
   /// Extends key sequence with a new part
   ///
+  /// # Panics
+  /// Key sequences defined by [`define_key_seq!`][define_key_seq] panic in debug builds
+  /// if the name of the new part matches a name of a static part
+  ///
   /// # Example
   /// ```
   /// use the_key::*;
@@ -99,6 +277,25 @@ pub trait KeyPartsSequence: Clone {
   /// ```
   fn extend<B: AsRef<[u8]>>(self, key_part_name: &'static str, bytes: B) -> Self;
 
+  /// Extends key sequence with a new part moving the bytes without copying
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend_owned("Part2", vec![30, 40]);
+  ///
+  ///   assert_eq!(
+  ///     key_seq.to_vec(),
+  ///     vec![10, 20, 30, 40]
+  ///   )
+  /// }
+  /// ```
+  fn extend_owned(self, key_part_name: &'static str, bytes: Vec<u8>) -> Self;
+
   /// Creates new [`the_key::Key`][Key] object
   ///
   /// # Example
@@ -118,363 +315,4207 @@ pub trait KeyPartsSequence: Clone {
   ///   );
   /// }
   /// ```
-  fn create_key<T: AsRef<[u8]>>(&self, key: T) -> Key<Self>;
+  fn create_key<T: AsRef<[u8]>>(&self, key: T) -> Key<'_, Self> {
+    let key = key.as_ref();
+    let mut result_key: Vec<u8> = Vec::with_capacity(self.get_prefix_len() + key.len());
 
-  #[doc(hidden)]
-  fn fmt_debug(
-    &self,
-    parts: &[KeyPartItem],
-    extensions: Option<&[KeyExtensionsItem]>,
-    f: &mut std::fmt::Formatter<'_>,
-  ) -> std::fmt::Result {
-    format_struct(parts, extensions, None, f)
+    self.create_key_into(key, &mut result_key);
+
+    Key::new(result_key, key.len(), self.get_extensions())
+      .with_dynamic_parts(self.get_dynamic_parts())
   }
-}
 
-#[derive(Clone)]
-pub struct Key<'a, T: KeyPartsSequence> {
-  bytes: Vec<u8>,
-  key_len: usize,
-  extensions: Option<&'a [KeyExtensionsItem]>,
-  phantom: PhantomData<T>,
-}
+  /// Creates new [`the_key::Key`][Key] object with the key encoded by [`the_key::OrderedEncode`][OrderedEncode],
+  /// so keys of the sequence are sorted in the order of the encoded values
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Users]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key_encoded((7u32, "ann", 3u64));
+  ///
+  ///   assert_eq!(key.get_key(), &[0, 0, 0, 7, b'a', b'n', b'n', 0, 0, 0, 0, 0, 0, 0, 0, 0, 3]);
+  ///   assert!(key.bytes() < key_seq.create_key_encoded((7u32, "bob", 1u64)).bytes());
+  /// }
+  /// ```
+  fn create_key_encoded<K: OrderedEncode>(&self, key: K) -> Key<'_, Self> {
+    let mut bytes = Vec::with_capacity(self.get_prefix_len() + key.encoded_len());
+    self.prefix_bytes_into(&mut bytes);
 
-impl<'a, T: KeyPartsSequence> Key<'a, T> {
-  pub fn new(bytes: Vec<u8>, key_len: usize, extensions: Option<&'a [KeyExtensionsItem]>) -> Self {
-    Self {
-      bytes,
-      key_len,
-      extensions,
-      phantom: PhantomData,
-    }
-  }
+    let prefix_len = bytes.len();
+    key.encode_ordered(&mut bytes);
+    let key_len = bytes.len() - prefix_len;
 
-  /// Returns key bytes
-  pub fn get_key(&self) -> &[u8] {
-    &self.bytes[self.bytes.len() - self.key_len..]
+    Key::new(bytes, key_len, self.get_extensions()).with_dynamic_parts(self.get_dynamic_parts())
   }
 
-  /// Returns prefix bytes
-  pub fn get_prefix(&self) -> &[u8] {
-    &self.bytes[..self.bytes.len() - self.key_len]
-  }
+  /// Creates new [`the_key::Key`][Key] object checking that the key isn't empty
+  /// if the key sequence requires a key
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20]);
+  /// define_key_seq!(UserById, [Users], require_key = true);
+  /// define_key_seq!(AllUsers, [Users]);
+  ///
+  /// fn main() {
+  ///   assert!(UserById::new().create_key_checked(&[30]).is_ok());
+  ///   assert_eq!(UserById::new().create_key_checked(&[]).unwrap_err(), Error::EmptyKey);
+  ///   assert!(AllUsers::new().create_key_checked(&[]).is_ok());
+  /// }
+  /// ```
+  fn create_key_checked<T: AsRef<[u8]>>(&self, key: T) -> Result<Key<'_, Self>, Error> {
+    let key = key.as_ref();
 
-  /// Moves out key bytes
-  pub fn to_vec(self) -> Vec<u8> {
-    self.bytes
-  }
-}
+    if key.is_empty() && Self::is_key_required() {
+      return Err(Error::EmptyKey);
+    }
 
-impl<'a, T: KeyPartsSequence> Into<Vec<u8>> for Key<'a, T> {
-  fn into(self) -> Vec<u8> {
-    self.to_vec()
+    Ok(self.create_key(key))
   }
-}
 
-impl<'a, T: KeyPartsSequence> std::fmt::Debug for Key<'a, T> {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    format_struct(
-      T::get_struct().as_slice(),
-      self.extensions,
-      Some((self.bytes.as_slice(), self.bytes.len())),
-      f,
-    )
+  /// Creates new [`the_key::KeyRef`][KeyRef] object. Keys up to
+  /// [`the_key::KEY_REF_INLINE_LEN`][KEY_REF_INLINE_LEN] bytes don't allocate
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key_ref(&[30]);
+  ///
+  ///   assert_eq!(key.bytes(), &[10, 20, 30]);
+  ///   assert!(!key.is_heap());
+  /// }
+  /// ```
+  fn create_key_ref<'b>(&'b self, key: &'b [u8]) -> KeyRef<'b> {
+    KeyRef::from_seq(self, key)
   }
-}
 
-impl<'a, T: KeyPartsSequence> AsRef<[u8]> for Key<'a, T> {
-  fn as_ref(&self) -> &[u8] {
-    self.bytes.as_slice()
+  /// Creates key bytes in a stack array. The array length `N` must be equal to
+  /// the prefix length (see [`get_prefix_len`][KeyPartsSequence::get_prefix_len]) plus the key length `K`
+  ///
+  /// # Panics
+  /// Panics if `N` isn't equal to the prefix length plus `K`
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key: [u8; 4] = MyKeySeq::new().create_key_array(&[30, 40]);
+  ///
+  ///   assert_eq!(key, [10, 20, 30, 40]);
+  /// }
+  /// ```
+  fn create_key_array<const N: usize, const K: usize>(&self, key: &[u8; K]) -> [u8; N] {
+    let prefix_len = self.get_prefix_len();
+
+    assert!(
+      N == prefix_len + K,
+      "array length {} is not equal to prefix length {} plus key length {}",
+      N,
+      prefix_len,
+      K,
+    );
+
+    let mut buf = [0; N];
+    let mut offset = 0;
+
+    self.write_prefix(|bytes| {
+      buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+      offset += bytes.len();
+    });
+    buf[offset..].copy_from_slice(key);
+
+    buf
   }
-}
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! count {
-  ($($name:ident),*) => {
-    {
-      let mut count = 0usize;
-      $(
-        let _ = stringify!($name);
-        count += 1;
-      )*
-      count
-    }
+  /// Appends key bytes to the buffer instead of allocating a new one
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let mut buf = vec![1];
+  ///
+  ///   MyKeySeq::new().create_key_into(&[30], &mut buf);
+  ///
+  ///   assert_eq!(buf, vec![1, 10, 20, 30]);
+  /// }
+  /// ```
+  fn create_key_into<T: AsRef<[u8]>>(&self, key: T, buf: &mut Vec<u8>) {
+    let key = key.as_ref();
+    buf.reserve_exact(self.get_prefix_len() + key.len());
+
+    self.write_prefix(|bytes| buf.extend_from_slice(bytes));
+    buf.extend_from_slice(key);
   }
-}
 
-/// Defines a key part. Each key part is a uniq struct whose implement trait [`the_key::KeyPart`][KeyPart]
-///
-/// # Example
-/// ```
-/// use the_key::*;
-///
-/// define_key_part!(KeyPartName, "key_part_bytes".as_bytes());
-/// ```
-#[macro_export]
-macro_rules! define_key_part {
-  ($name:ident, $bytes:expr) => {
-    #[derive(Debug)]
-    pub struct $name {
-      key_part_name: &'static str,
-      bytes: &'static [u8],
-    }
+  /// Creates new [`the_key::Key`][Key] object with the bytes allocated by `alloc`
+  ///
+  /// # Example
+  /// ```
+  /// #![feature(allocator_api)]
+  /// use std::alloc::Global;
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key_in(&[30], Global);
+  ///
+  ///   assert_eq!(key.get_key(), &[30]);
+  ///   assert_eq!(format!("{:?}", key), "Part1[10, 20] -> Key=[30]");
+  ///   assert_eq!(key.into_bytes(), vec![10, 20, 30]);
+  /// }
+  /// ```
+  #[cfg(feature = "allocator_api")]
+  fn create_key_in<T: AsRef<[u8]>, A: std::alloc::Allocator>(
+    &self,
+    key: T,
+    alloc: A,
+  ) -> Key<'_, Self, A> {
+    let key = key.as_ref();
+    let mut buf = Vec::with_capacity_in(self.get_prefix_len() + key.len(), alloc);
 
-    impl KeyPart for $name {
-      fn new() -> Self {
-        $name::new()
-      }
+    self.write_prefix(|bytes| buf.extend_from_slice(bytes));
+    buf.extend_from_slice(key);
 
-      fn get_name(&self) -> &'static str {
-        self.key_part_name
-      }
+    Key::new_in(buf, key.len(), self.get_extensions()).with_dynamic_parts(self.get_dynamic_parts())
+  }
 
-      fn get_bytes(&self) -> &'static [u8] {
-        self.bytes
-      }
-    }
+  /// Appends prefix bytes without a key to the buffer
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let mut buf = vec![1];
+  ///
+  ///   MyKeySeq::new().extend("Part2", &[30]).prefix_bytes_into(&mut buf);
+  ///
+  ///   assert_eq!(buf, vec![1, 10, 20, 30]);
+  /// }
+  /// ```
+  fn prefix_bytes_into(&self, buf: &mut Vec<u8>) {
+    buf.reserve_exact(self.get_prefix_len());
 
-    impl $name {
-      pub const fn new() -> Self {
-        const KEY_PART: &'static [u8] = $bytes;
-        const KEY_PART_NAME: &'static str = &stringify!($name);
+    self.write_prefix(|bytes| buf.extend_from_slice(bytes));
+  }
 
-        Self {
-          key_part_name: &KEY_PART_NAME,
-          bytes: &KEY_PART,
-        }
+  /// Appends key bytes to the [`bytes::BytesMut`] buffer
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let mut buf = bytes::BytesMut::new();
+  ///
+  ///   MyKeySeq::new().create_key_into_bytes(&[30], &mut buf);
+  ///
+  ///   assert_eq!(&buf[..], &[10, 20, 30]);
+  /// }
+  /// ```
+  #[cfg(feature = "bytes")]
+  fn create_key_into_bytes<T: AsRef<[u8]>>(&self, key: T, buf: &mut bytes::BytesMut) {
+    use bytes::BufMut;
+
+    let key = key.as_ref();
+    buf.reserve(self.get_prefix_len() + key.len());
+
+    self.write_prefix(|bytes| buf.put_slice(bytes));
+    buf.put_slice(key);
+  }
+
+  /// Returns length of the prefix bytes including extensions and separators
+  fn get_prefix_len(&self) -> usize;
+
+  /// Passes prefix bytes chunks to `write` in order
+  #[doc(hidden)]
+  fn write_prefix<F: FnMut(&[u8])>(&self, write: F);
+
+  /// Extends key sequence with a new part in the given sort order.
+  /// For [`Order::Descending`][Order::Descending] one's complement of the bytes is stored
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new()
+  ///     .extend_ordered("Category", &[1], Order::Ascending)
+  ///     .extend_ordered("Timestamp", &[0, 5], Order::Descending);
+  ///
+  ///   assert_eq!(
+  ///     key_seq.to_vec(),
+  ///     vec![10, 20, 1, 255, 250]
+  ///   )
+  /// }
+  /// ```
+  fn extend_ordered<B: AsRef<[u8]>>(
+    self,
+    key_part_name: &'static str,
+    bytes: B,
+    order: Order,
+  ) -> Self {
+    match order {
+      Order::Ascending => self.extend(key_part_name, bytes),
+      Order::Descending => {
+        self.extend_owned(key_part_name, bytes.as_ref().iter().map(|b| !b).collect())
       }
     }
-  };
-}
+  }
+
+  /// Creates new [`the_key::Key`][Key] object with the key if it's `Some` or a prefix-only key otherwise.
+  /// Debug output of a prefix-only key has no `Key=` segment
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(
+  ///     format!("{:?}", key_seq.create_key_opt(Some(&[30]))),
+  ///     "Part1[10, 20] -> Key=[30]",
+  ///   );
+  ///   assert_eq!(
+  ///     format!("{:?}", key_seq.create_key_opt(None::<&[u8]>)),
+  ///     "Part1[10, 20]",
+  ///   );
+  /// }
+  /// ```
+  fn create_key_opt<T: AsRef<[u8]>>(&self, key: Option<T>) -> Key<'_, Self> {
+    match key {
+      Some(key) => self.create_key(key),
+      None => {
+        let mut key = self.create_key([]);
+        key.has_key = false;
+
+        key
+      }
+    }
+  }
+
+  /// Parses raw bytes into [`the_key::Key`][Key] checking that they start with the key sequence prefix.
+  /// All bytes after the prefix become the key
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(key_seq.parse_key(&[10, 20, 30]).unwrap().get_key(), &[30]);
+  ///   assert!(key_seq.parse_key(&[10, 30, 30]).is_err());
+  /// }
+  /// ```
+  fn parse_key(&self, bytes: &[u8]) -> Result<Key<'_, Self>, Error> {
+    let mut prefix = Vec::with_capacity(self.get_prefix_len());
+    self.create_key_into([], &mut prefix);
+
+    parse_with_prefix(self, &prefix, bytes)
+  }
+
+  /// Turns an iterator over raw keys into an iterator over parsed [`the_key::Key`][Key] objects.
+  /// Yields an error for the first raw key outside of the key sequence prefix and stops after it
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let raw: Vec<&[u8]> = vec![&[10, 20, 30], &[10, 20, 40], &[11, 0], &[10, 20, 50]];
+  ///   let keys: Vec<_> = key_seq.scan(raw.into_iter()).collect();
+  ///
+  ///   assert_eq!(keys.len(), 3);
+  ///   assert_eq!(keys[1].as_ref().unwrap().get_key(), &[40]);
+  ///   assert!(keys[2].is_err());
+  /// }
+  /// ```
+  fn scan<'b, I>(&self, raw: I) -> impl Iterator<Item = Result<Key<'_, Self>, Error>>
+  where
+    I: Iterator<Item = &'b [u8]>,
+  {
+    let mut prefix = Vec::with_capacity(self.get_prefix_len());
+    self.create_key_into([], &mut prefix);
+
+    let mut stopped = false;
+
+    raw.map_while(move |bytes| {
+      if stopped {
+        return None;
+      }
+
+      let key = parse_with_prefix(self, &prefix, bytes);
+      stopped = key.is_err();
+
+      Some(key)
+    })
+  }
+
+  /// Creates new [`the_key::RawKeyBuilder`][RawKeyBuilder] with the prefix pre-written
+  fn raw_builder(&self) -> RawKeyBuilder<'_, Self> {
+    RawKeyBuilder::new(self.create_key([]))
+  }
+
+  /// Creates new [`the_key::RawKeyBuilder`][RawKeyBuilder] with the prefix pre-written
+  /// and exact capacity for `key_capacity` bytes of the key
+  fn raw_builder_with_capacity(&self, key_capacity: usize) -> RawKeyBuilder<'_, Self> {
+    let mut bytes = Vec::with_capacity(self.get_prefix_len() + key_capacity);
+    self.create_key_into([], &mut bytes);
+
+    let key =
+      Key::new(bytes, 0, self.get_extensions()).with_dynamic_parts(self.get_dynamic_parts());
+
+    RawKeyBuilder::new(key)
+  }
+
+  /// Creates new [`the_key::KeyFmtBuilder`][KeyFmtBuilder] with the prefix pre-written
+  fn fmt_builder(&self) -> KeyFmtBuilder<'_, Self> {
+    KeyFmtBuilder::new(self.create_key([]))
+  }
+
+  /// Returns an inclusive end bound for a scan up to `end_key`: the key followed by
+  /// [`the_key::INCLUSIVE_END_FILL_LEN`][INCLUSIVE_END_FILL_LEN] `0xFF` bytes.
+  /// The bound covers every key starting with `end_key` whose remaining bytes are not longer than the fill
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let end = MyKeySeq::new().inclusive_end(&[30]);
+  ///
+  ///   assert_eq!(&end[..3], &[10, 20, 30]);
+  ///   assert_eq!(&end[3..], &[0xFF; INCLUSIVE_END_FILL_LEN]);
+  /// }
+  /// ```
+  fn inclusive_end<T: AsRef<[u8]>>(&self, end_key: T) -> Vec<u8> {
+    let end_key = end_key.as_ref();
+    let mut end =
+      Vec::with_capacity(self.get_prefix_len() + end_key.len() + INCLUSIVE_END_FILL_LEN);
+
+    self.create_key_into(end_key, &mut end);
+    end.resize(end.len() + INCLUSIVE_END_FILL_LEN, 0xFF);
+
+    end
+  }
+
+  /// Returns the smallest key which is greater than `key`: the key followed by `0x00` byte.
+  /// The result stays under the prefix, so it's an inclusive start of the next page when
+  /// paginating after the last seen `key`
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(key_seq.key_after(&[30]), vec![10, 20, 30, 0]);
+  ///   assert_eq!(key_seq.key_after(&[]), vec![10, 20, 0]);
+  /// }
+  /// ```
+  fn key_after<T: AsRef<[u8]>>(&self, key: T) -> Vec<u8> {
+    let key = key.as_ref();
+    let mut after = Vec::with_capacity(self.get_prefix_len() + key.len() + 1);
+
+    self.write_prefix(|bytes| after.extend_from_slice(bytes));
+    after.extend_from_slice(key);
+    after.push(0);
+
+    after
+  }
+
+  /// Returns an exclusive upper bound of the keys under the prefix to start a backward scan from,
+  /// see [`util::next_prefix`].
+  /// Returns `None` if the prefix is empty or consists of `0xFF` bytes only,
+  /// in that case there is no upper bound and a backward scan starts from the last key of the store.
+  ///
+  /// Pair it with [`reverse_seek_end`][KeyPartsSequence::reverse_seek_end] as the inclusive lower bound, e.g.
+  /// set RocksDB `iterate_upper_bound` to the start (or leave it unset on `None`) and call `seek_to_last`,
+  /// or iterate sled `range(end..start).rev()` (or `range(end..).rev()` on `None`)
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_part!(Part2, &[0xFF]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  /// define_key_seq!(MaxKeySeq, [Part2]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(MyKeySeq::new().reverse_seek_start(), Some(vec![10, 21]));
+  ///   assert_eq!(MaxKeySeq::new().reverse_seek_start(), None);
+  /// }
+  /// ```
+  fn reverse_seek_start(&self) -> Option<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(self.get_prefix_len());
+    self.prefix_bytes_into(&mut prefix);
+
+    util::next_prefix(&prefix)
+  }
+
+  /// Returns an inclusive lower bound of the keys under the prefix to stop a backward scan at,
+  /// which is the prefix itself. See [`reverse_seek_start`][KeyPartsSequence::reverse_seek_start]
+  fn reverse_seek_end(&self) -> Vec<u8> {
+    let mut end = Vec::with_capacity(self.get_prefix_len());
+    self.prefix_bytes_into(&mut end);
+
+    end
+  }
+
+  /// Returns prefix bytes without the last part and its separator. The last extension is
+  /// dropped if there are extensions, otherwise the last static part is dropped
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20]);
+  /// define_key_part!(Photos, &[30]);
+  /// define_key_seq!(MyKeySeq, [Users, Photos]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(key_seq.parent_prefix(), vec![10, 20]);
+  ///   assert_eq!(key_seq.extend("PhotoId", &[40]).parent_prefix(), vec![10, 20, 30]);
+  /// }
+  /// ```
+  fn parent_prefix(&self) -> Vec<u8> {
+    let last_part_len = self
+      .get_extensions()
+      .and_then(<[_]>::last)
+      .map(|(_, bytes)| bytes.len())
+      .or_else(|| self.get_layout_parts().last().map(|(_, bytes)| bytes.len()));
+
+    let mut prefix = Vec::new();
+    self.prefix_bytes_into(&mut prefix);
+
+    if let Some(last_part_len) = last_part_len {
+      prefix.truncate(prefix.len() - last_part_len - Self::get_separator().len());
+    }
+
+    prefix
+  }
+
+  /// Returns cumulative prefixes after each part including its separator,
+  /// static parts first and extensions next. The last one is the whole prefix
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10]);
+  /// define_key_part!(Profiles, &[20]);
+  /// define_key_seq!(MyKeySeq, [Users, Profiles]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(
+  ///     MyKeySeq::new().extend("UserId", &[30]).intermediate_prefixes(),
+  ///     vec![vec![10], vec![10, 20], vec![10, 20, 30]],
+  ///   );
+  /// }
+  /// ```
+  fn intermediate_prefixes(&self) -> Vec<Vec<u8>> {
+    let separator = Self::get_separator();
+    let parts = self.get_layout_parts();
+    let parts = parts.iter().map(|(_, bytes)| *bytes);
+    let extensions = self.extensions_iter().map(|(_, bytes)| bytes);
+
+    let mut prefix = Vec::with_capacity(self.get_prefix_len());
+
+    parts
+      .chain(extensions)
+      .map(|bytes| {
+        prefix.extend_from_slice(bytes);
+        prefix.extend_from_slice(separator);
+
+        prefix.clone()
+      })
+      .collect()
+  }
+
+  /// Checks that the prefix can be split back into the parts without knowing the key sequence
+  /// extensions. Static parts have fixed length, so the layout is unambiguous if
+  /// - there are no extensions, or
+  /// - the separator isn't empty and the first occurrence of the separator in each extension
+  ///   followed by the separator is the trailing separator itself
+  ///
+  /// Extensions don't carry their length, so a layout with extensions and without separator
+  /// is considered ambiguous even if the extensions are fixed-width numbers
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  /// define_key_seq!(MySepKeySeq, [Part1], sep = &[0]);
+  ///
+  /// fn main() {
+  ///   assert!(MyKeySeq::new().is_unambiguously_parseable());
+  ///   assert!(!MyKeySeq::new().extend("Part2", b"name").is_unambiguously_parseable());
+  ///   assert!(MySepKeySeq::new().extend("Part2", b"name").is_unambiguously_parseable());
+  /// }
+  /// ```
+  fn is_unambiguously_parseable(&self) -> bool {
+    let separator = Self::get_separator();
+
+    if separator.is_empty() {
+      return self.extensions_iter().next().is_none();
+    }
+
+    self.extensions_iter().all(|(_, bytes)| {
+      [bytes, separator]
+        .concat()
+        .windows(separator.len())
+        .position(|window| window == separator)
+        == Some(bytes.len())
+    })
+  }
+
+  /// Returns length of the longest common leading bytes of prefixes of two key sequences
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_part!(Part2, &[30]);
+  /// define_key_part!(Part3, &[40]);
+  /// define_key_seq!(MyKeySeq, [Part1, Part2]);
+  /// define_key_seq!(OtherKeySeq, [Part1, Part3]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(MyKeySeq::new().common_prefix_len(&OtherKeySeq::new()), 2);
+  /// }
+  /// ```
+  fn common_prefix_len<B: KeyPartsSequence>(&self, other: &B) -> usize {
+    let mut prefix = Vec::new();
+    let mut other_prefix = Vec::new();
+
+    self.prefix_bytes_into(&mut prefix);
+    other.prefix_bytes_into(&mut other_prefix);
+
+    prefix
+      .iter()
+      .zip(&other_prefix)
+      .take_while(|(a, b)| a == b)
+      .count()
+  }
+
+  /// Returns a hash of the key sequence layout parts names and bytes and the separator,
+  /// see [`get_layout_parts`][KeyPartsSequence::get_layout_parts].
+  /// Extensions are not hashed since they usually carry runtime values like ids, so the hash
+  /// doesn't tell apart sequences which differ only by extensions
+  ///
+  /// The hash is 64-bit FNV-1a over the length-prefixed name and bytes of each part in order
+  /// followed by the length-prefixed separator, where lengths are little-endian `u64`.
+  /// The algorithm is a part of the public api and won't change between crate versions
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(key_seq.layout_hash(), key_seq.clone().extend("Part2", &[30]).layout_hash());
+  ///
+  ///   let dynamic_seq = DynamicSeq::from_parts(vec![Part::new_dynamic("Part1", vec![10, 20])]);
+  ///
+  ///   assert_eq!(key_seq.layout_hash(), dynamic_seq.layout_hash());
+  ///   assert_eq!(dynamic_seq.layout_hash(), dynamic_seq.clone().extend("Part2", &[30]).layout_hash());
+  /// }
+  /// ```
+  fn layout_hash(&self) -> u64 {
+    hash::layout_hash(self.get_layout_parts(), Self::get_separator())
+  }
+
+  /// Extends key sequence with a `bool` part encoded as `0` or `1`
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new()
+  ///     .extend_bool("Active", true)
+  ///     .extend_bool("Deleted", false);
+  ///
+  ///   assert_eq!(key_seq.to_vec(), vec![10, 20, 1, 0]);
+  /// }
+  /// ```
+  fn extend_bool(self, key_part_name: &'static str, value: bool) -> Self {
+    self.extend_u8(key_part_name, value as u8)
+  }
+
+  /// Extends key sequence with a single byte `u8` part
+  fn extend_u8(self, key_part_name: &'static str, value: u8) -> Self {
+    self.extend(key_part_name, [value])
+  }
+
+  /// Extends key sequence with a `u16` number part in the given byte order.
+  /// Only [`Endianness::Big`][Endianness::Big] keeps keys sorted in numeric order
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new()
+  ///     .extend_u16("Big", 1, Endianness::Big)
+  ///     .extend_u16("Little", 1, Endianness::Little);
+  ///
+  ///   assert_eq!(key_seq.to_vec(), vec![10, 20, 0, 1, 1, 0]);
+  /// }
+  /// ```
+  fn extend_u16(self, key_part_name: &'static str, value: u16, endianness: Endianness) -> Self {
+    match endianness {
+      Endianness::Big => self.extend(key_part_name, value.to_be_bytes()),
+      Endianness::Little => self.extend(key_part_name, value.to_le_bytes()),
+    }
+  }
+
+  /// Extends key sequence with a `u32` number part in the given byte order.
+  /// Only [`Endianness::Big`][Endianness::Big] keeps keys sorted in numeric order
+  fn extend_u32(self, key_part_name: &'static str, value: u32, endianness: Endianness) -> Self {
+    match endianness {
+      Endianness::Big => self.extend(key_part_name, value.to_be_bytes()),
+      Endianness::Little => self.extend(key_part_name, value.to_le_bytes()),
+    }
+  }
+
+  /// Extends key sequence with a `u64` number part in the given byte order.
+  /// Only [`Endianness::Big`][Endianness::Big] keeps keys sorted in numeric order
+  fn extend_u64(self, key_part_name: &'static str, value: u64, endianness: Endianness) -> Self {
+    match endianness {
+      Endianness::Big => self.extend(key_part_name, value.to_be_bytes()),
+      Endianness::Little => self.extend(key_part_name, value.to_le_bytes()),
+    }
+  }
+
+  /// Extends key sequence with a `u128` number part in the given byte order.
+  /// Only [`Endianness::Big`][Endianness::Big] keeps keys sorted in numeric order
+  fn extend_u128(self, key_part_name: &'static str, value: u128, endianness: Endianness) -> Self {
+    match endianness {
+      Endianness::Big => self.extend(key_part_name, value.to_be_bytes()),
+      Endianness::Little => self.extend(key_part_name, value.to_le_bytes()),
+    }
+  }
+
+  /// Returns number of possible keys of `key_width` bytes under the key sequence prefix.
+  /// Saturates to `u128::MAX` for keys of 16 bytes and longer
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(MyKeySeq::new().key_space(2), 65536);
+  /// }
+  /// ```
+  fn key_space(&self, key_width: usize) -> u128 {
+    256u128.saturating_pow(key_width.min(u32::MAX as usize) as u32)
+  }
+
+  /// Returns JSON representation of the key sequence parts
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(
+  ///     MyKeySeq::new().to_json(),
+  ///     r#"{"parts":[{"name":"Part1","bytes":[10,20]}]}"#,
+  ///   );
+  /// }
+  /// ```
+  fn to_json(&self) -> String {
+    format_json(
+      &self.get_layout_parts(),
+      self.get_extensions(),
+      Self::get_separator(),
+      None,
+    )
+  }
+
+  /// Returns the key sequence layout with widths of the parts instead of their bytes
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20]);
+  /// define_key_part!(Profiles, &[30, 40]);
+  /// define_key_seq!(MyKeySeq, [Users, Profiles]);
+  ///
+  /// fn main() {
+  ///   assert_eq!(
+  ///     MyKeySeq::new().render_template(),
+  ///     "Users(2 bytes) | Profiles(2 bytes) | <key>",
+  ///   );
+  /// }
+  /// ```
+  fn render_template(&self) -> String {
+    format_template(
+      &self.get_layout_parts(),
+      self.get_extensions(),
+      Self::get_separator(),
+    )
+  }
+
+  #[doc(hidden)]
+  fn fmt_debug(
+    &self,
+    parts: &[(&'static str, &[u8])],
+    extensions: Option<&[KeyExtensionsItem]>,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    format_struct(
+      parts,
+      extensions,
+      Self::get_separator(),
+      None,
+      BytesFormat::Decimal,
+      f,
+    )
+  }
+}
+
+#[cfg(not(feature = "allocator_api"))]
+#[derive(Clone)]
+pub struct Key<'a, T: KeyPartsSequence> {
+  bytes: Vec<u8>,
+  key_len: usize,
+  has_key: bool,
+  extensions: Option<&'a [KeyExtensionsItem]>,
+  dynamic_parts: &'a [KeyExtensionsItem],
+  phantom: PhantomData<T>,
+}
+
+/// Key whose bytes are allocated by `A`. Available with `allocator_api` feature
+#[cfg(feature = "allocator_api")]
+#[derive(Clone)]
+pub struct Key<'a, T: KeyPartsSequence, A: std::alloc::Allocator = std::alloc::Global> {
+  bytes: Vec<u8, A>,
+  key_len: usize,
+  has_key: bool,
+  extensions: Option<&'a [KeyExtensionsItem]>,
+  dynamic_parts: &'a [KeyExtensionsItem],
+  phantom: PhantomData<T>,
+}
+
+/// Implements items for [`the_key::Key`][Key] with any allocator when `allocator_api` feature is enabled
+/// and for [`the_key::Key`][Key] with the global allocator otherwise
+macro_rules! impl_key {
+  (impl $trait:path { $($body:tt)* }) => {
+    #[cfg(not(feature = "allocator_api"))]
+    impl<'a, T: KeyPartsSequence> $trait for Key<'a, T> {
+      $($body)*
+    }
+
+    #[cfg(feature = "allocator_api")]
+    impl<'a, T: KeyPartsSequence, A: std::alloc::Allocator> $trait for Key<'a, T, A> {
+      $($body)*
+    }
+  };
+  ($($body:tt)*) => {
+    #[cfg(not(feature = "allocator_api"))]
+    impl<'a, T: KeyPartsSequence> Key<'a, T> {
+      $($body)*
+    }
+
+    #[cfg(feature = "allocator_api")]
+    impl<'a, T: KeyPartsSequence, A: std::alloc::Allocator> Key<'a, T, A> {
+      $($body)*
+    }
+  };
+}
+
+impl_key! {
+  /// Sets the parts of the dynamic key sequence the key belongs to
+  pub(crate) fn with_dynamic_parts(mut self, dynamic_parts: &'a [KeyExtensionsItem]) -> Self {
+    self.dynamic_parts = dynamic_parts;
+    self
+  }
+
+  /// Returns names and bytes of the layout parts, see [`KeyPartsSequence::get_layout_parts`]
+  fn layout_parts(&self) -> Vec<(&'static str, &[u8])> {
+    T::get_struct()
+      .into_iter()
+      .chain(
+        self
+          .dynamic_parts
+          .iter()
+          .map(|(name, bytes)| (*name, bytes.as_slice())),
+      )
+      .collect()
+  }
+
+  /// Returns all bytes of the key including the prefix
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(key_seq.create_key(&[30]).bytes(), &[10, 20, 30]);
+  /// }
+  /// ```
+  pub fn bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+
+  /// Returns key bytes
+  pub fn get_key(&self) -> &[u8] {
+    &self.bytes[self.bytes.len() - self.key_len..]
+  }
+
+  /// Returns key bytes as a string slice borrowed from the key if they are valid UTF-8
+  pub fn get_key_str(&self) -> Result<&str, std::str::Utf8Error> {
+    std::str::from_utf8(self.get_key())
+  }
+
+  /// Returns key bytes as a string replacing invalid UTF-8 sequences.
+  /// Borrows from the key when bytes are valid UTF-8
+  pub fn get_key_str_lossy(&self) -> Cow<'_, str> {
+    String::from_utf8_lossy(self.get_key())
+  }
+
+  /// Returns prefix bytes
+  pub fn get_prefix(&self) -> &[u8] {
+    &self.bytes[..self.bytes.len() - self.key_len]
+  }
+
+  /// Checks that the key bytes start with bytes of the static parts, separators and extensions
+  /// and the key starts right after them. Extensions names are not validated, only their bytes
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert!(key_seq.create_key(&[30]).is_valid());
+  ///   assert!(!Key::<MyKeySeq>::new(vec![10, 21, 30], 1, None).is_valid());
+  /// }
+  /// ```
+  pub fn is_valid(&self) -> bool {
+    if self.key_len > self.bytes.len() {
+      return false;
+    }
+
+    let separator = T::get_separator();
+    let parts = self.layout_parts();
+    let extensions = self.extensions.unwrap_or_default();
+    let chunks = parts
+      .iter()
+      .map(|(_, bytes)| *bytes)
+      .chain(extensions.iter().map(|(_, bytes)| bytes.as_slice()))
+      .flat_map(|bytes| [bytes, separator]);
+
+    let mut offset = 0;
+
+    for chunk in chunks {
+      if !self.bytes[offset..].starts_with(chunk) {
+        return false;
+      }
+
+      offset += chunk.len();
+    }
+
+    offset + self.key_len == self.bytes.len()
+  }
+
+  /// Shortens the key to `new_key_len` bytes in place keeping the prefix
+  ///
+  /// # Panics
+  /// Panics if `new_key_len` is greater than the current key length
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let mut key = key_seq.create_key(&[30, 40, 50]);
+  ///
+  ///   key.truncate_key(1);
+  ///
+  ///   assert_eq!(key.to_vec(), vec![10, 20, 30]);
+  /// }
+  /// ```
+  pub fn truncate_key(&mut self, new_key_len: usize) {
+    assert!(
+      new_key_len <= self.key_len,
+      "new key length {} is greater than key length {}",
+      new_key_len,
+      self.key_len,
+    );
+
+    let prefix_len = self.bytes.len() - self.key_len;
+
+    self.bytes.truncate(prefix_len + new_key_len);
+    self.key_len = new_key_len;
+  }
+
+  /// Replaces the key bytes keeping the prefix and reusing the allocation
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key(&[30, 40]).rebuild_with_key(&[50]);
+  ///
+  ///   assert_eq!(key.get_key(), &[50]);
+  ///   assert_eq!(key.to_vec(), vec![10, 20, 50]);
+  /// }
+  /// ```
+  pub fn rebuild_with_key<K: AsRef<[u8]>>(mut self, key: K) -> Self {
+    self.set_key_bytes(key.as_ref());
+
+    self
+  }
+
+  /// Overwrites the key bytes in place. A key of the same length is copied over the old one
+  /// without reallocation, otherwise the key bytes are truncated or extended
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let mut key = key_seq.create_key(0u64.to_be_bytes());
+  ///
+  ///   for counter in 1..=3u64 {
+  ///     key.set_key_bytes(&counter.to_be_bytes());
+  ///   }
+  ///
+  ///   assert_eq!(key.get_key(), &3u64.to_be_bytes());
+  /// }
+  /// ```
+  pub fn set_key_bytes(&mut self, key: &[u8]) {
+    let prefix_len = self.bytes.len() - self.key_len;
+
+    if key.len() == self.key_len {
+      self.bytes[prefix_len..].copy_from_slice(key);
+    } else {
+      self.bytes.truncate(prefix_len);
+      self.bytes.extend_from_slice(key);
+      self.key_len = key.len();
+    }
+
+    self.has_key = true;
+  }
+
+  /// Splits the key bytes at the start of the first part with the given name.
+  /// Returns `None` if there is no such part or it starts past the end of the key bytes
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, &[10, 20]);
+  /// define_key_part!(Photos, &[30]);
+  /// define_key_seq!(MyKeySeq, [Users, Photos]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key(&[40]);
+  ///
+  ///   assert_eq!(key.split_at_part("Photos"), Some((&[10, 20][..], &[30, 40][..])));
+  ///   assert_eq!(key.split_at_part("Albums"), None);
+  /// }
+  /// ```
+  pub fn split_at_part(&self, name: &str) -> Option<(&[u8], &[u8])> {
+    self
+      .part_ranges()
+      .into_iter()
+      .find(|(part_name, _)| *part_name == name)
+      .and_then(|(_, range)| self.bytes.split_at_checked(range.start))
+  }
+
+  /// Returns the byte of the single byte part with the given name.
+  /// Returns `None` if there is no such part or the part isn't single byte
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend_u8("Kind", 3);
+  ///   let key = key_seq.create_key(&[30]);
+  ///
+  ///   assert_eq!(key.get_u8("Kind"), Some(3));
+  ///   assert_eq!(key.get_u8("Part1"), None);
+  /// }
+  /// ```
+  pub fn get_u8(&self, name: &str) -> Option<u8> {
+    self
+      .part_ranges()
+      .into_iter()
+      .find(|(part_name, _)| *part_name == name)
+      .and_then(|(_, range)| match self.bytes.get(range) {
+        Some([byte]) => Some(*byte),
+        _ => None,
+      })
+  }
+
+  /// Returns the value of the `bool` part with the given name.
+  /// Returns `None` if there is no such part or the part isn't a single `0` or `1` byte
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend_bool("Active", true);
+  ///
+  ///   assert_eq!(key_seq.create_key(&[30]).get_bool("Active"), Some(true));
+  /// }
+  /// ```
+  pub fn get_bool(&self, name: &str) -> Option<bool> {
+    match self.get_u8(name)? {
+      0 => Some(false),
+      1 => Some(true),
+      _ => None,
+    }
+  }
+
+  /// Splits all key bytes into the static parts bytes, the extensions bytes and the key bytes.
+  /// Separators belong to the segment of the part they follow.
+  /// Returns `None` if the bytes are too short for the key length and the static parts,
+  /// e.g. for a truncated key created with [`Key::new`]
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new().extend("Part2", &[30]);
+  ///   let key = key_seq.create_key(&[40]);
+  ///
+  ///   assert_eq!(key.as_parts_bytes(), Some((&[10, 20][..], &[30][..], &[40][..])));
+  /// }
+  /// ```
+  pub fn as_parts_bytes(&self) -> Option<(&[u8], &[u8], &[u8])> {
+    let separator_len = T::get_separator().len();
+    let static_len: usize = self
+      .layout_parts()
+      .iter()
+      .map(|(_, bytes)| bytes.len() + separator_len)
+      .sum();
+
+    let prefix_len = self.bytes.len().checked_sub(self.key_len)?;
+    let (prefix, key) = self.bytes.split_at_checked(prefix_len)?;
+    let (static_prefix, extensions) = prefix.split_at_checked(static_len)?;
+
+    Some((static_prefix, extensions, key))
+  }
+
+  /// Returns names and byte ranges of the static parts and the extensions without separators
+  fn part_ranges(&self) -> Vec<(&'static str, std::ops::Range<usize>)> {
+    let separator_len = T::get_separator().len();
+    let parts = self.layout_parts();
+    let parts = parts.iter().map(|(name, bytes)| (*name, bytes.len()));
+    let extensions = self
+      .extensions
+      .unwrap_or_default()
+      .iter()
+      .map(|(name, bytes)| (*name, bytes.len()));
+
+    let mut offset = 0;
+
+    parts
+      .chain(extensions)
+      .map(|(name, len)| {
+        let range = offset..offset + len;
+        offset += len + separator_len;
+
+        (name, range)
+      })
+      .collect()
+  }
+
+  /// Returns JSON representation of the key parts and the key bytes
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(
+  ///     key_seq.create_key(&[30]).to_json(),
+  ///     r#"{"parts":[{"name":"Part1","bytes":[10,20]}],"key":[30]}"#,
+  ///   );
+  /// }
+  /// ```
+  pub fn to_json(&self) -> String {
+    format_json(
+      &self.layout_parts(),
+      self.extensions,
+      T::get_separator(),
+      self.has_key.then(|| self.get_key()),
+    )
+  }
+
+  /// Feeds all key bytes to the hasher with a single `Hasher::write` call. Hash stability
+  /// depends only on the hasher: hashers built by `RandomState` are randomly seeded per process,
+  /// and `DefaultHasher::new()` is deterministic but its algorithm may change between Rust releases.
+  /// Use `hash64` of the `xxhash` feature or another fixed-algorithm hasher for hashes which must be
+  /// stable across processes or releases
+  ///
+  /// # Example
+  /// ```
+  /// use std::collections::hash_map::DefaultHasher;
+  /// use std::hash::Hasher;
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let mut hasher = DefaultHasher::new();
+  ///   let mut bytes_hasher = DefaultHasher::new();
+  ///
+  ///   key_seq.create_key(&[30]).hash_with(&mut hasher);
+  ///   bytes_hasher.write(&[10, 20, 30]);
+  ///
+  ///   assert_eq!(hasher.finish(), bytes_hasher.finish());
+  /// }
+  /// ```
+  pub fn hash_with<H: std::hash::Hasher>(&self, hasher: &mut H) {
+    hasher.write(&self.bytes);
+  }
+
+  /// Returns XXH3 64-bit hash of all key bytes. The hash is unseeded, so it's stable
+  /// across processes, platforms and crate versions
+  #[cfg(feature = "xxhash")]
+  pub fn hash64(&self) -> u64 {
+    xxhash_rust::xxh3::xxh3_64(&self.bytes)
+  }
+
+  /// Converts the key into [`the_key::OwnedKey`][OwnedKey] which doesn't borrow
+  /// the key sequence and renders the same `Debug` output
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let owned_key = {
+  ///     let key_seq = MyKeySeq::new().extend("Part2", &[30]);
+  ///
+  ///     key_seq.create_key(&[40]).into_owned_with_seq()
+  ///   };
+  ///
+  ///   assert_eq!(
+  ///     format!("{:?}", owned_key),
+  ///     "Part1[10, 20] -> Part2[30] -> Key=[40]",
+  ///   );
+  /// }
+  /// ```
+  pub fn into_owned_with_seq(self) -> OwnedKey {
+    OwnedKey::from(self)
+  }
+
+  /// Rebuilds the key under prefix of another key sequence keeping the key bytes
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(OldPart, &[10, 20]);
+  /// define_key_part!(NewPart, &[30]);
+  /// define_key_seq!(OldKeySeq, [OldPart]);
+  /// define_key_seq!(NewKeySeq, [NewPart]);
+  ///
+  /// fn main() {
+  ///   let old_key_seq = OldKeySeq::new();
+  ///   let new_key_seq = NewKeySeq::new();
+  ///   let key = old_key_seq.create_key(&[50, 60]);
+  ///
+  ///   assert_eq!(
+  ///     key.replace_prefix(&new_key_seq).to_vec(),
+  ///     vec![30, 50, 60],
+  ///   );
+  /// }
+  /// ```
+  pub fn replace_prefix<'b, U: KeyPartsSequence>(self, new_seq: &'b U) -> Key<'b, U> {
+    new_seq.create_key(self.get_key())
+  }
+}
+
+/// Emits the methods comparing a key with another key, which may use a different allocator
+macro_rules! impl_key_compare {
+  ([$($other_generics:tt)*] $other:ty) => {
+    /// Checks that the key bytes are a strict prefix of the other key bytes.
+    /// Comparison is purely byte-based, so equal keys are not prefixes of each other
+    ///
+    /// # Example
+    /// ```
+    /// use the_key::*;
+    /// define_key_part!(Part1, &[10, 20]);
+    /// define_key_seq!(MyKeySeq, [Part1]);
+    ///
+    /// fn main() {
+    ///   let key_seq = MyKeySeq::new();
+    ///   let parent = key_seq.create_key(&[30]);
+    ///   let child = key_seq.create_key(&[30, 40]);
+    ///
+    ///   assert!(parent.is_prefix_of(&child));
+    ///   assert!(!child.is_prefix_of(&parent));
+    /// }
+    /// ```
+    pub fn is_prefix_of<$($other_generics)*>(&self, other: &$other) -> bool {
+      other.bytes.len() > self.bytes.len() && other.bytes.starts_with(&self.bytes)
+    }
+
+    /// Returns index of the first byte where the keys differ or `None` if the keys are equal
+    ///
+    /// # Example
+    /// ```
+    /// use the_key::*;
+    /// define_key_part!(Part1, &[10, 20]);
+    /// define_key_seq!(MyKeySeq, [Part1]);
+    ///
+    /// fn main() {
+    ///   let key_seq = MyKeySeq::new();
+    ///   let key = key_seq.create_key(&[30, 40]);
+    ///
+    ///   assert_eq!(key.diff(&key_seq.create_key(&[30, 41])), Some(3));
+    ///   assert_eq!(key.diff(&key_seq.create_key(&[30])), Some(3));
+    ///   assert_eq!(key.diff(&key_seq.create_key(&[30, 40])), None);
+    /// }
+    /// ```
+    pub fn diff<$($other_generics)*>(&self, other: &$other) -> Option<usize> {
+      self
+        .bytes
+        .iter()
+        .zip(&other.bytes)
+        .position(|(a, b)| a != b)
+        .or_else(|| {
+          (self.bytes.len() != other.bytes.len()).then(|| self.bytes.len().min(other.bytes.len()))
+        })
+    }
+
+    /// Describes where the keys differ in terms of the key parts of this key
+    ///
+    /// # Example
+    /// ```
+    /// use the_key::*;
+    /// define_key_part!(Part1, &[10, 20]);
+    /// define_key_seq!(MyKeySeq, [Part1]);
+    ///
+    /// fn main() {
+    ///   let key_seq = MyKeySeq::new();
+    ///   let key = key_seq.create_key(&[30, 40]);
+    ///
+    ///   assert_eq!(
+    ///     key.diff_report(&key_seq.create_key(&[30, 41])),
+    ///     "keys differ at byte 3 in Key: [30, 40] != [30, 41]",
+    ///   );
+    /// }
+    /// ```
+    pub fn diff_report<$($other_generics)*>(&self, other: &$other) -> String {
+      let index = match self.diff(other) {
+        Some(index) => index,
+        None => return String::from("keys are equal"),
+      };
+
+      let separator_len = T::get_separator().len();
+      let prefix_len = self.bytes.len() - self.key_len;
+
+      let (segment, range) = self
+        .part_ranges()
+        .into_iter()
+        .find_map(|(name, range)| {
+          if range.contains(&index) {
+            Some((name.to_string(), range))
+          } else if (range.end..range.end + separator_len).contains(&index) {
+            Some((
+              format!("separator after {}", name),
+              range.end..range.end + separator_len,
+            ))
+          } else {
+            None
+          }
+        })
+        .unwrap_or_else(|| (String::from("Key"), prefix_len..usize::MAX));
+
+      let segment_bytes = |bytes: &[u8]| -> Vec<u8> {
+        bytes[range.start.min(bytes.len())..range.end.min(bytes.len())].to_vec()
+      };
+
+      format!(
+        "keys differ at byte {} in {}: {:?} != {:?}",
+        index,
+        segment,
+        segment_bytes(&self.bytes),
+        segment_bytes(&other.bytes),
+      )
+    }
+  };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+impl<'a, T: KeyPartsSequence> Key<'a, T> {
+  impl_key_compare!([] Key<'_, T>);
+}
+
+#[cfg(feature = "allocator_api")]
+impl<'a, T: KeyPartsSequence, A: std::alloc::Allocator> Key<'a, T, A> {
+  impl_key_compare!([B: std::alloc::Allocator] Key<'_, T, B>);
+}
+
+impl<'a, T: KeyPartsSequence> Key<'a, T> {
+  pub fn new(bytes: Vec<u8>, key_len: usize, extensions: Option<&'a [KeyExtensionsItem]>) -> Self {
+    Self {
+      bytes,
+      key_len,
+      has_key: true,
+      extensions,
+      dynamic_parts: &[],
+      phantom: PhantomData,
+    }
+  }
+
+  /// Moves out the key bytes leaving only the prefix in the key
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let mut key = key_seq.create_key(&[30, 40]);
+  ///
+  ///   assert_eq!(key.split_off_key(), vec![30, 40]);
+  ///   assert_eq!(key.to_vec(), vec![10, 20]);
+  /// }
+  /// ```
+  pub fn split_off_key(&mut self) -> Vec<u8> {
+    let prefix_len = self.bytes.len() - self.key_len;
+    self.key_len = 0;
+
+    self.bytes.split_off(prefix_len)
+  }
+
+  /// Splits key bytes into segments at the given offsets relative to the key start
+  ///
+  /// # Panics
+  /// Panics if offsets are not ascending or exceed the key length
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Part1, &[10, 20]);
+  /// define_key_seq!(MyKeySeq, [Part1]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///   let key = key_seq.create_key(&[30, 31, 40, 50, 51]);
+  ///
+  ///   assert_eq!(
+  ///     key.split_key_at(&[2, 3]),
+  ///     vec![&[30, 31][..], &[40], &[50, 51]],
+  ///   );
+  /// }
+  /// ```
+  pub fn split_key_at(&self, offsets: &[usize]) -> Vec<&[u8]> {
+    let key = self.get_key();
+    let mut segments = Vec::with_capacity(offsets.len() + 1);
+    let mut start = 0;
+
+    for &offset in offsets {
+      segments.push(&key[start..offset]);
+      start = offset;
+    }
+
+    segments.push(&key[start..]);
+
+    segments
+  }
+
+  /// Moves out key bytes
+  pub fn to_vec(self) -> Vec<u8> {
+    self.bytes
+  }
+
+  /// Returns `Debug` rendering of the key which shows printable ASCII bytes as characters
+  /// and other bytes as `\xNN`
+  ///
+  /// # Example
+  /// ```
+  /// use the_key::*;
+  /// define_key_part!(Users, b"users");
+  /// define_key_seq!(MyKeySeq, [Users]);
+  ///
+  /// fn main() {
+  ///   let key_seq = MyKeySeq::new();
+  ///
+  ///   assert_eq!(
+  ///     format!("{:?}", key_seq.create_key(&[0, 1]).debug_ascii()),
+  ///     r#"Users["users"] -> Key=[\x00\x01]"#,
+  ///   );
+  /// }
+  /// ```
+  pub fn debug_ascii(&self) -> impl std::fmt::Debug + '_ {
+    DebugAscii(self)
+  }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<'a, T: KeyPartsSequence, A: std::alloc::Allocator> Key<'a, T, A> {
+  /// Creates a key from bytes allocated by `A`
+  pub fn new_in(
+    bytes: Vec<u8, A>,
+    key_len: usize,
+    extensions: Option<&'a [KeyExtensionsItem]>,
+  ) -> Self {
+    Self {
+      bytes,
+      key_len,
+      has_key: true,
+      extensions,
+      dynamic_parts: &[],
+      phantom: PhantomData,
+    }
+  }
+
+  /// Moves out key bytes keeping their allocator
+  pub fn into_bytes(self) -> Vec<u8, A> {
+    self.bytes
+  }
+}
+
+impl<'a, T: KeyPartsSequence> From<Key<'a, T>> for Vec<u8> {
+  fn from(key: Key<'a, T>) -> Self {
+    key.to_vec()
+  }
+}
+
+impl_key! {
+  fn fmt_with(
+    &self,
+    bytes_format: BytesFormat,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    format_struct(
+      &self.layout_parts(),
+      self.extensions,
+      T::get_separator(),
+      self
+        .has_key
+        .then_some((self.bytes.as_slice(), self.bytes.len())),
+      bytes_format,
+      f,
+    )
+  }
+}
+
+/// Converts the key into an array if the key bytes length is exactly `N`
+///
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use the_key::*;
+/// define_key_part!(Part1, &[10, 20]);
+/// define_key_seq!(MyKeySeq, [Part1]);
+///
+/// fn main() {
+///   let key_seq = MyKeySeq::new();
+///
+///   assert_eq!(<[u8; 3]>::try_from(key_seq.create_key(&[30])), Ok([10, 20, 30]));
+///   assert_eq!(
+///     <[u8; 2]>::try_from(key_seq.create_key(&[30])),
+///     Err(Error::LengthMismatch { len: 3, expected: 2 }),
+///   );
+/// }
+/// ```
+impl<'a, T: KeyPartsSequence, const N: usize> TryFrom<Key<'a, T>> for [u8; N] {
+  type Error = Error;
+
+  fn try_from(key: Key<'a, T>) -> Result<Self, Self::Error> {
+    let len = key.bytes.len();
+
+    key
+      .to_vec()
+      .try_into()
+      .map_err(|_| Error::LengthMismatch { len, expected: N })
+  }
+}
+
+impl_key! {
+  impl std::fmt::Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      self.fmt_with(BytesFormat::Decimal, f)
+    }
+  }
+}
+
+struct DebugAscii<'k, 'a, T: KeyPartsSequence>(&'k Key<'a, T>);
+
+impl<'k, 'a, T: KeyPartsSequence> std::fmt::Debug for DebugAscii<'k, 'a, T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.0.fmt_with(BytesFormat::Ascii, f)
+  }
+}
+
+impl_key! {
+  impl AsRef<[u8]> {
+    fn as_ref(&self) -> &[u8] {
+      self.bytes.as_slice()
+    }
+  }
+}
+
+fn parse_with_prefix<'a, T: KeyPartsSequence>(
+  seq: &'a T,
+  prefix: &[u8],
+  bytes: &[u8],
+) -> Result<Key<'a, T>, Error> {
+  if bytes.len() < prefix.len() {
+    return Err(Error::TooShort {
+      len: bytes.len(),
+      min: prefix.len(),
+    });
+  }
+
+  if !bytes.starts_with(prefix) {
+    return Err(Error::PrefixMismatch {
+      expected: prefix.to_vec(),
+      found: bytes[..prefix.len()].to_vec(),
+    });
+  }
+
+  Ok(
+    Key::new(
+      bytes.to_vec(),
+      bytes.len() - prefix.len(),
+      seq.get_extensions(),
+    )
+    .with_dynamic_parts(seq.get_dynamic_parts()),
+  )
+}
+
+/// Concatenates bytes of the keys into one buffer in the given order
+///
+/// # Example
+/// ```
+/// use the_key::*;
+/// define_key_part!(Part1, &[10, 20]);
+/// define_key_seq!(MyKeySeq, [Part1]);
+///
+/// fn main() {
+///   let key_seq = MyKeySeq::new();
+///   let keys = [key_seq.create_key(&[30]), key_seq.create_key(&[40])];
+///
+///   assert_eq!(
+///     concat_keys(&keys),
+///     vec![10, 20, 30, 10, 20, 40],
+///   );
+/// }
+/// ```
+pub fn concat_keys<T: KeyPartsSequence>(keys: &[Key<T>]) -> Vec<u8> {
+  let len = keys.iter().map(|key| key.bytes.len()).sum();
+  let mut result: Vec<u8> = Vec::with_capacity(len);
+
+  keys.iter().for_each(|key| {
+    result.extend_from_slice(&key.bytes);
+  });
+
+  result
+}
+
+/// Checks that two key sequences have the same static parts names and bytes in the same order
+/// and the same separator
+///
+/// # Example
+/// ```
+/// use the_key::*;
+/// define_key_part!(Part1, &[10, 20]);
+/// define_key_part!(Part2, &[30, 40]);
+/// define_key_seq!(MyKeySeq, [Part1, Part2]);
+/// define_key_seq!(MyOtherKeySeq, [Part1, Part2]);
+///
+/// fn main() {
+///   assert!(same_layout::<MyKeySeq, MyOtherKeySeq>());
+/// }
+/// ```
+pub fn same_layout<A: KeyPartsSequence, B: KeyPartsSequence>() -> bool {
+  A::get_struct() == B::get_struct() && A::get_separator() == B::get_separator()
+}
+
+/// Number of `0xFF` bytes appended by [`KeyPartsSequence::inclusive_end`][KeyPartsSequence::inclusive_end]
+pub const INCLUSIVE_END_FILL_LEN: usize = 16;
+
+/// Maximal number of static parts in a key sequence defined by [`define_key_seq!`][define_key_seq]
+// `concat!` can't read a const, so the literal `64` in the assert message of `define_key_seq!`
+// must be updated together with this value
+pub const MAX_KEY_PARTS: usize = 64;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! count {
+  ($($name:ident),*) => {
+    {
+      let mut count = 0usize;
+      $(
+        let _ = stringify!($name);
+        count += 1;
+      )*
+      count
+    }
+  }
+}
+
+/// Defines a key part. Each key part is a uniq struct whose implement trait [`the_key::KeyPart`][KeyPart]
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(KeyPartName, "key_part_bytes".as_bytes());
+/// ```
+///
+/// Optional `align` argument checks at compile time that the key part bytes length is a multiple of it
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(AlignedKeyPart, &[1, 2, 3, 4], align = 4);
+/// ```
+///
+/// ```compile_fail
+/// use the_key::*;
+///
+/// define_key_part!(AlignedKeyPart, &[1, 2, 3], align = 4);
+/// ```
+///
+/// Optional `desc` argument sets the key part description
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(DescribedKeyPart, &[1, 2, 3, 4], align = 4, desc = "Key part with description");
+///
+/// fn main() {
+///   assert_eq!(DescribedKeyPart::new().get_description(), Some("Key part with description"));
+/// }
+/// ```
+///
+/// The key part is `pub` unless a visibility is given before the name
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(pub(crate) PrivateKeyPart, &[1, 2]);
+/// ```
+///
+/// The macro doesn't need anything else of the crate in scope
+///
+/// ```
+/// use the_key::{define_key_part, define_key_part_cstr, KeyPart};
+///
+/// define_key_part!(Users, &[10, 20]);
+/// define_key_part_cstr!(Photos, "photos");
+///
+/// fn main() {
+///   assert_eq!(Users::new().get_name(), "Users");
+///   assert_eq!(Photos::new().get_bytes(), b"photos\0");
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_key_part {
+  ($name:ident, $($rest:tt)*) => {
+    $crate::define_key_part!(pub $name, $($rest)*);
+  };
+  ($vis:vis $name:ident, $bytes:expr, align = $align:expr $(, desc = $desc:expr)?) => {
+    $crate::define_key_part!($vis $name, $bytes $(, desc = $desc)?);
+
+    const _: () = assert!(
+      $name::BYTES.len() % $align == 0,
+      concat!(
+        "key part ",
+        stringify!($name),
+        " bytes length must be a multiple of ",
+        stringify!($align),
+      ),
+    );
+  };
+  ($vis:vis $name:ident, $bytes:expr, desc = $desc:expr) => {
+    $crate::define_key_part!(@define [$vis] $name, $bytes, Some($desc));
+  };
+  ($vis:vis $name:ident, $bytes:expr) => {
+    $crate::define_key_part!(@define [$vis] $name, $bytes, None);
+  };
+  (@define [$vis:vis] $name:ident, $bytes:expr, $desc:expr) => {
+    #[derive(Debug)]
+    $vis struct $name {
+      key_part_name: &'static str,
+      bytes: &'static [u8],
+      description: Option<&'static str>,
+    }
+
+    impl $crate::KeyPart for $name {
+      fn new() -> Self {
+        $name::new()
+      }
+
+      fn get_name(&self) -> &'static str {
+        self.key_part_name
+      }
+
+      fn get_bytes(&self) -> &'static [u8] {
+        self.bytes
+      }
+
+      fn get_description(&self) -> Option<&'static str> {
+        self.description
+      }
+    }
+
+    impl $name {
+      /// Key part bytes
+      $vis const BYTES: &'static [u8] = $bytes;
+
+      /// Key part description
+      $vis const DESCRIPTION: Option<&'static str> = $desc;
+
+      $vis const fn new() -> Self {
+        const KEY_PART_NAME: &'static str = &stringify!($name);
+
+        Self {
+          key_part_name: &KEY_PART_NAME,
+          bytes: Self::BYTES,
+          description: Self::DESCRIPTION,
+        }
+      }
+    }
+  };
+}
+
+/// Defines several key parts at once. Each entry expands to [`define_key_part!`][define_key_part]
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// define_key_parts! {
+///   Users => &[11, 11],
+///   pub(crate) Photos => &[33, 33],
+/// }
+///
+/// fn main() {
+///   assert_eq!(Users::BYTES, &[11, 11]);
+///   assert_eq!(Photos::BYTES, &[33, 33]);
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_key_parts {
+  ($($vis:vis $name:ident => $bytes:expr),* $(,)?) => {
+    $(
+      $crate::define_key_part!($vis $name, $bytes);
+    )*
+  };
+}
+
+/// Defines a key part whose bytes are the string followed by `0x00` terminator,
+/// so the key part bytes can't be a prefix of bytes of another such key part
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// define_key_part_cstr!(Users, "users");
+///
+/// fn main() {
+///   assert_eq!(Users::BYTES, b"users\0");
+/// }
+/// ```
+///
+/// The string can't contain null bytes
+///
+/// ```compile_fail
+/// use the_key::*;
+///
+/// define_key_part_cstr!(Users, "us\0ers");
+/// ```
+#[macro_export]
+macro_rules! define_key_part_cstr {
+  ($vis:vis $name:ident, $str:literal) => {
+    $crate::define_key_part!($vis $name, concat!($str, "\0").as_bytes());
+
+    const _: () = {
+      let mut i = 0;
+
+      while i < $name::BYTES.len() - 1 {
+        assert!(
+          $name::BYTES[i] != 0,
+          concat!(
+            "key part ",
+            stringify!($name),
+            " string must not contain null bytes"
+          ),
+        );
+
+        i += 1;
+      }
+    };
+  };
+}
+
+/// Defines a key sequence. Each key part is a uniq struct whose implement trait [`the_key::KeyPartsSequence`][KeyPartsSequence]
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(KeyPart1, "key_part_1".as_bytes());
+/// define_key_part!(KeyPart2, "key_part_2".as_bytes());
+/// define_key_seq!(KeyPartsSeq, [KeyPart1, KeyPart2]);
+/// ```
+///
+///
+/// Optional `sep` argument defines separator bytes written after each part including extensions
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(KeyPart1, &[10]);
+/// define_key_part!(KeyPart2, &[20]);
+/// define_key_seq!(KeyPartsSeq, [KeyPart1, KeyPart2], sep = &[0, 1]);
+///
+/// fn main() {
+///   let key_seq = KeyPartsSeq::new();
+///
+///   assert_eq!(
+///     key_seq.create_key(&[30]).to_vec(),
+///     vec![10, 0, 1, 20, 0, 1, 30],
+///   );
+/// }
+/// ```
+///
+/// A key sequence can't have more than [`the_key::MAX_KEY_PARTS`][MAX_KEY_PARTS] static parts
+///
+/// ```compile_fail
+/// use the_key::*;
+///
+/// define_key_part!(P, &[1]);
+/// define_key_seq!(KeyPartsSeq, [
+///   P, P, P, P, P, P, P, P, P, P, P, P, P, P, P, P,
+///   P, P, P, P, P, P, P, P, P, P, P, P, P, P, P, P,
+///   P, P, P, P, P, P, P, P, P, P, P, P, P, P, P, P,
+///   P, P, P, P, P, P, P, P, P, P, P, P, P, P, P, P,
+///   P
+/// ]);
+/// ```
+///
+/// Optional `require_key` argument makes [`create_key_checked`][KeyPartsSequence::create_key_checked]
+/// reject empty keys
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(KeyPart1, &[10]);
+/// define_key_seq!(KeyPartsSeq, [KeyPart1], sep = &[0], require_key = true);
+///
+/// fn main() {
+///   assert!(KeyPartsSeq::new().create_key_checked(&[]).is_err());
+/// }
+/// ```
+///
+/// The key sequence is `pub` unless a visibility is given before the name
+///
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(pub(crate) KeyPart1, &[10]);
+/// define_key_seq!(pub(crate) KeyPartsSeq, [KeyPart1]);
+/// ```
+#[macro_export]
+macro_rules! define_key_seq {
+  ($name:ident, $($rest:tt)*) => {
+    $crate::define_key_seq!(pub $name, $($rest)*);
+  };
+  ($vis:vis $name:ident, [$($key_part:ident),*]) => {
+    $crate::define_key_seq!($vis $name, [$($key_part),*], sep = &[]);
+  };
+  ($vis:vis $name:ident, [$($key_part:ident),*], require_key = $require_key:expr) => {
+    $crate::define_key_seq!($vis $name, [$($key_part),*], sep = &[], require_key = $require_key);
+  };
+  ($vis:vis $name:ident, [$($key_part:ident),*], sep = $sep:expr) => {
+    $crate::define_key_seq!($vis $name, [$($key_part),*], sep = $sep, require_key = false);
+  };
+  ($vis:vis $name:ident, [$($key_part:ident),*], sep = $sep:expr, require_key = $require_key:expr) => {
+    $crate::define_key_seq!(@define [$vis] $name, [$($key_part),*], sep = $sep, require_key = $require_key);
+  };
+  (@define [$vis:vis] $name:ident, [$($key_part:ident),*], sep = $sep:expr, require_key = $require_key:expr) => {
+    const _: () = assert!(
+      $crate::count!($($key_part),*) <= $crate::MAX_KEY_PARTS,
+      concat!(
+        "key sequences support at most 64 static parts (the_key::MAX_KEY_PARTS), ",
+        stringify!($name),
+        " has more",
+      ),
+    );
+
+    #[derive(Clone)]
+    $vis struct $name {
+      parts: [KeyPartItem; $crate::count!($($key_part),*)],
+      extensions: Option<Vec<KeyExtensionsItem>>,
+      len: usize,
+    }
+
+    impl $name {
+      $vis fn new() -> Self {
+        let mut len = 0;
+        let parts: [KeyPartItem; $crate::count!($($key_part),*)] = [
+          $({
+            let key_part = $key_part::new();
+            let bytes = key_part.get_bytes();
+
+            len += bytes.len() + Self::SEPARATOR.len();
+
+            (key_part.get_name(), bytes)
+          },)*
+        ];
+
+        Self {
+          len,
+          parts,
+          extensions: None,
+        }
+      }
+
+      /// Separator bytes written after each part
+      $vis const SEPARATOR: &'static [u8] = $sep;
+
+      /// Whether keys of the sequence must not be empty
+      $vis const REQUIRE_KEY: bool = $require_key;
+
+      /// Name of the key sequence
+      $vis const NAME: &'static str = stringify!($name);
+
+      /// Length of the static parts bytes including separators
+      #[allow(dead_code)]
+      $vis const PREFIX_LEN: usize = 0 $(+ $key_part::BYTES.len() + Self::SEPARATOR.len())*;
+
+      /// Offset and length of each static part bytes in the key
+      #[allow(dead_code)]
+      $vis const OFFSETS: &'static [(usize, usize)] = &{
+        let lens = [$($key_part::BYTES.len()),*];
+        let mut offsets = [(0usize, 0usize); $crate::count!($($key_part),*)];
+        let mut offset = 0;
+        let mut i = 0;
+
+        while i < offsets.len() {
+          offsets[i] = (offset, lens[i]);
+          offset += lens[i] + Self::SEPARATOR.len();
+          i += 1;
+        }
+
+        offsets
+      };
+
+      /// Static parts bytes including separators. They are concatenated once per type
+      /// on the first call and shared by all keys of the type
+      $vis fn static_prefix() -> &'static [u8] {
+        static PREFIX: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+
+        PREFIX.get_or_init(|| {
+          let mut prefix = Vec::with_capacity(Self::PREFIX_LEN);
+
+          $(
+            prefix.extend_from_slice($key_part::BYTES);
+            prefix.extend_from_slice(Self::SEPARATOR);
+          )*
+
+          prefix
+        })
+      }
+
+      // This just a public api
+      #[allow(dead_code)]
+      fn to_vec(&self) -> Vec<u8> {
+        self.create_key(&[]).to_vec()
+      }
+    }
+
+    impl KeyPartsSequence for $name {
+      fn new() -> Self {
+        $name::new()
+      }
+
+      fn get_struct() -> Vec<KeyPartItem> {
+        let mut parts = Vec::new();
+
+        $({
+          let key = $key_part::new();
+          parts.push((key.get_name(), key.get_bytes()));
+        })*
+
+        parts
+      }
+
+      fn describe(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut parts = Vec::new();
+
+        $({
+          let key = $key_part::new();
+          parts.push((key.get_name(), key.get_description()));
+        })*
+
+        parts
+      }
+
+      fn get_extensions(&self) -> Option<&[KeyExtensionsItem]> {
+        self.extensions.as_ref().map(|v| v.as_slice())
+      }
+
+      fn get_separator() -> &'static [u8] {
+        Self::SEPARATOR
+      }
+
+      fn extend<B: AsRef<[u8]>>(self, key_part_name: &'static str, bytes: B) -> Self {
+        self.extend_owned(key_part_name, bytes.as_ref().to_vec())
+      }
+
+      fn extend_owned(mut self, key_part_name: &'static str, key_bytes: Vec<u8>) -> Self {
+        debug_assert!(
+          self.parts.iter().all(|(name, _)| *name != key_part_name),
+          "extension {:?} shadows a static part of {}",
+          key_part_name,
+          stringify!($name),
+        );
+
+        self.len += key_bytes.len() + Self::SEPARATOR.len();
+
+        self.extensions = match self.extensions {
+          Some(mut extensions) => {
+            extensions.push((key_part_name, key_bytes));
+
+            Some(extensions)
+          },
+          None => Some(vec![(key_part_name, key_bytes)]),
+        };
+
+        self
+      }
+
+      fn get_prefix_len(&self) -> usize {
+        self.len
+      }
+
+      fn is_key_required() -> bool {
+        Self::REQUIRE_KEY
+      }
+
+      fn seq_name(&self) -> &'static str {
+        Self::NAME
+      }
+
+      fn write_prefix<F: FnMut(&[u8])>(&self, mut write: F) {
+        write(Self::static_prefix());
+
+        if let Some(extensions) = &self.extensions {
+          extensions.iter().for_each(|(_, bytes)| {
+            write(bytes);
+            write(Self::SEPARATOR);
+          });
+        }
+      }
+    }
+
+    impl std::fmt::Debug for $name {
+      fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug(
+          &self.parts,
+          self.extensions.as_ref().map(|v| v.as_slice()),
+          f
+        )
+      }
+    }
+  };
+}
+
+/// Defines a key sequence with fixed-width named fields of the key.
+/// Besides the key sequence it defines a trait with getters of the fields which is implemented for [`the_key::Key`][Key].
+/// A getter returns `None` if the key is too short to contain the field, e.g. it was created by `create_key`
+/// with a key of another length
+///
+/// # Example
+/// ```
+/// use the_key::*;
+///
+/// define_key_part!(Tenants, &[10, 20]);
+/// define_key_layout!(TenantsLayout, TenantsLayoutFields, [Tenants], {
+///   tenant: 2,
+///   shard: 1,
+///   id: 3,
+/// });
+///
+/// fn main() {
+///   let layout = TenantsLayout::new();
+///   let key = layout.parse(&[10, 20, 1, 1, 2, 3, 3, 3]).unwrap();
+///
+///   assert_eq!(TenantsLayout::KEY_LEN, 6);
+///   assert_eq!(key.tenant(), Some(&[1, 1][..]));
+///   assert_eq!(key.shard(), Some(&[2][..]));
+///   assert_eq!(key.id(), Some(&[3, 3, 3][..]));
+///   assert_eq!(layout.create_key(&[1, 1]).shard(), None);
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_key_layout {
+  ($name:ident, $fields:ident, [$($key_part:ident),*], { $($field:ident: $width:expr),* $(,)? }) => {
+    $crate::define_key_seq!($name, [$($key_part),*]);
+
+    impl $name {
+      /// Length of the key described by the layout fields
+      pub const KEY_LEN: usize = 0 $(+ $width)*;
+
+      /// Parses bytes into [`the_key::Key`][Key] checking the prefix and the key length
+      pub fn parse<B: AsRef<[u8]>>(&self, bytes: B) -> Result<Key<'_, Self>, $crate::Error> {
+        let bytes = bytes.as_ref();
+        let prefix = self.to_vec();
+        let len = prefix.len() + Self::KEY_LEN;
+
+        if bytes.len() < len {
+          return Err($crate::Error::TooShort {
+            len: bytes.len(),
+            min: len,
+          });
+        }
+
+        if bytes.len() > len {
+          return Err($crate::Error::KeyTooLong {
+            len: bytes.len() - prefix.len(),
+            max: Self::KEY_LEN,
+          });
+        }
+
+        if !bytes.starts_with(&prefix) {
+          return Err($crate::Error::PrefixMismatch {
+            found: bytes[..prefix.len()].to_vec(),
+            expected: prefix,
+          });
+        }
+
+        Ok(Key::new(bytes.to_vec(), Self::KEY_LEN, self.get_extensions()))
+      }
+    }
+
+    pub trait $fields {
+      $(fn $field(&self) -> Option<&[u8]>;)*
+    }
+
+    impl<'a> $fields for Key<'a, $name> {
+      $crate::define_key_layout!(@getters 0usize; $($field: $width,)*);
+    }
+  };
+  (@getters $offset:expr; $field:ident: $width:expr, $($rest:tt)*) => {
+    fn $field(&self) -> Option<&[u8]> {
+      self.get_key().get($offset..$offset + $width)
+    }
+
+    $crate::define_key_layout!(@getters $offset + $width; $($rest)*);
+  };
+  (@getters $offset:expr;) => {};
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use test::Bencher;
+
+  #[test]
+  fn key_part_test() {
+    define_key_part!(KeyPart1, "my_key_part_1".as_bytes());
+
+    let kp = KeyPart1::new();
+
+    assert_eq!(
+      kp.get_name(),
+      "KeyPart1",
+    );
+
+    assert_eq!(
+      kp.get_bytes(),
+      b"my_key_part_1",
+    );
+  }
+
+  #[test]
+  fn key_part_align_test() {
+    define_key_part!(KeyPart1, &[10, 20, 30, 40], align = 4);
+    define_key_part!(KeyPart2, &[50, 60], align = 2);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    assert_eq!(
+      MyPrefixSeq::new().create_key([70]).to_vec(),
+      vec![10, 20, 30, 40, 50, 60, 70],
+    );
+  }
+
+  #[test]
+  fn key_parts_batch_test() {
+    define_key_parts! {
+      Users => &[11, 11],
+      Photos => &[33, 33],
+      Profiles => "profiles".as_bytes(),
+    }
+
+    define_key_seq!(MyPrefixSeq, [Users, Profiles, Photos]);
+
+    assert_eq!(Users::new().get_name(), "Users");
+    assert_eq!(Profiles::BYTES, b"profiles");
+    assert_eq!(
+      format!("{:?}", MyPrefixSeq::new().create_key([1])),
+      "Users[11, 11] -> Profiles[112, 114, 111, 102, 105, 108, 101, 115] -> Photos[33, 33] -> Key=[1]"
+    );
+  }
+
+  #[test]
+  fn key_part_cstr_test() {
+    define_key_part_cstr!(Users, "users");
+    define_key_part_cstr!(UsersArchive, "users_archive");
+    define_key_seq!(MyPrefixSeq, [Users]);
+
+    let key_part = Users::new();
+
+    assert_eq!(key_part.get_bytes(), b"users\0");
+    assert_eq!(key_part.get_bytes().last(), Some(&0));
+    assert!(!UsersArchive::BYTES.starts_with(Users::BYTES));
+    assert_eq!(
+      format!("{:?}", MyPrefixSeq::new().create_key([1])),
+      "Users[117, 115, 101, 114, 115, 0] -> Key=[1]"
+    );
+  }
+
+  #[test]
+  fn key_part_description_test() {
+    define_key_part!(Users, &[10, 20], desc = "Registered users");
+    define_key_part!(Photos, &[30, 40], align = 2, desc = "User photos");
+    define_key_part!(Tags, &[50]);
+    define_key_seq!(MyPrefixSeq, [Users, Photos, Tags]);
+
+    assert_eq!(Users::new().get_description(), Some("Registered users"));
+    assert_eq!(Photos::DESCRIPTION, Some("User photos"));
+    assert_eq!(Tags::new().get_description(), None);
+    assert_eq!(
+      MyPrefixSeq::new().describe(),
+      vec![
+        ("Users", Some("Registered users")),
+        ("Photos", Some("User photos")),
+        ("Tags", None),
+      ]
+    );
+    assert_eq!(DynamicSeq::new().describe(), vec![]);
+    assert_eq!(
+      DynamicSeq::from_parts(vec![Part::new_dynamic("Users", vec![10, 20])])
+        .extend("UserId", [1])
+        .describe(),
+      vec![("Users", None)],
+    );
+  }
+
+  #[deny(unreachable_pub)]
+  mod private_keys {
+    use the_key::*;
+
+    define_key_part!(pub(crate) KeyPart1, &[10, 20]);
+    define_key_part_cstr!(pub(crate) KeyPart3, "key_part_3");
+    define_key_part!(pub(crate) KeyPart2, &[30, 31], align = 2, desc = "Second key part");
+    define_key_seq!(pub(crate) PrivateSeq, [KeyPart1, KeyPart2], sep = &[0]);
+    define_key_seq!(pub(super) OtherPrivateSeq, [KeyPart2]);
+  }
+
+  #[test]
+  fn key_seq_visibility_test() {
+    let key_seq = private_keys::PrivateSeq::new();
+
+    assert_eq!(
+      key_seq.create_key([40]).to_vec(),
+      vec![10, 20, 0, 30, 31, 0, 40]
+    );
+    assert_eq!(
+      private_keys::OtherPrivateSeq::new()
+        .create_key([40])
+        .to_vec(),
+      vec![30, 31, 40]
+    );
+    assert_eq!(private_keys::KeyPart2::BYTES, &[30, 31]);
+    assert_eq!(private_keys::KeyPart3::BYTES, b"key_part_3\0");
+  }
+
+  #[test]
+  fn key_seq_offsets_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30]);
+    define_key_part!(KeyPart3, &[40, 50, 60]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2, KeyPart3]);
+
+    assert_eq!(MyPrefixSeq::PREFIX_LEN, 6);
+    assert_eq!(MyPrefixSeq::OFFSETS, &[(0, 2), (2, 1), (3, 3)]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([70]);
+    let (offset, len) = MyPrefixSeq::OFFSETS[2];
+
+    assert_eq!(&key.as_ref()[offset..offset + len], &[40, 50, 60]);
+  }
+
+  #[cfg(feature = "derive")]
+  #[test]
+  fn key_part_derive_test() {
+    #[derive(KeyPart)]
+    #[key_part(bytes = &[10, 20], desc = "First key part")]
+    struct KeyPart1;
+
+    #[derive(KeyPart)]
+    #[key_part(bytes = b"key_part_2")]
+    struct KeyPart2;
+
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let kp = KeyPart2::new();
+
+    assert_eq!(kp.get_name(), "KeyPart2");
+    assert_eq!(kp.get_bytes(), b"key_part_2");
+    assert_eq!(
+      format!("{:?}", MyPrefixSeq::new().create_key([30])),
+      "KeyPart1[10, 20] -> KeyPart2[107, 101, 121, 95, 112, 97, 114, 116, 95, 50] -> Key=[30]",
+    );
+    assert_eq!(
+      MyPrefixSeq::new().describe(),
+      vec![("KeyPart1", Some("First key part")), ("KeyPart2", None)]
+    );
+  }
+
+  #[test]
+  fn key_from_seq_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    assert_eq!(
+      MyPrefixSeq::new().create_key([50, 60]).to_vec(),
+      vec![10, 20, 30, 40, 50, 60],
+    )
+  }
+
+  #[test]
+  fn key_from_seq_opt_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+
+    let key = key_seq.create_key_opt(Some([40, 50]));
+    assert_eq!(key.as_ref(), &[10, 20, 30, 40, 50]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> ExtensionPart1[30] -> Key=[40, 50]",
+    );
+
+    let key = key_seq.create_key_opt(None::<[u8; 0]>);
+    assert_eq!(key.as_ref(), &[10, 20, 30]);
+    assert_eq!(key.get_key(), &[] as &[u8]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> ExtensionPart1[30]",
+    );
+    assert_eq!(
+      key.to_json(),
+      r#"{"parts":[{"name":"KeyPart1","bytes":[10,20]},{"name":"ExtensionPart1","bytes":[30]}]}"#,
+    );
+    assert_eq!(
+      format!("{:?}", key_seq.create_key_opt(Some([]))),
+      "KeyPart1[10, 20] -> ExtensionPart1[30] -> Key=[]",
+    );
+  }
+
+  #[test]
+  fn key_get_key_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([70, 80]);
+
+    let expected: &[u8] = &[70, 80];
+    assert_eq!(key.get_key(), expected);
+  }
+
+  #[test]
+  fn key_bytes_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key = key_seq.create_key([40]);
+
+    assert_eq!(key.bytes(), &[10, 20, 30, 40]);
+    assert_eq!(key.bytes(), key.as_ref());
+  }
+
+  #[test]
+  fn key_get_key_str_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key("user_1");
+
+    assert_eq!(key.get_key_str(), Ok("user_1"));
+    assert!(matches!(key.get_key_str_lossy(), Cow::Borrowed("user_1")));
+
+    let key = key_seq.create_key([b'a', 0xFF, b'b']);
+
+    assert!(key.get_key_str().is_err());
+    assert_eq!(key.get_key_str_lossy(), "a\u{FFFD}b");
+  }
+
+  #[test]
+  fn key_get_prefix_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([70, 80]);
+
+    assert_eq!(key.get_prefix(), &[10, 20, 30, 40])
+  }
+
+  #[test]
+  fn key_is_valid_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let extensions = key_seq.get_extensions();
+
+    assert!(key_seq.create_key([40]).is_valid());
+    assert!(key_seq.create_key([]).is_valid());
+    assert!(Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 0, 40], 1, extensions).is_valid());
+    assert!(!Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 0, 40], 2, extensions).is_valid());
+    assert!(!Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 0, 40], 7, extensions).is_valid());
+    assert!(!Key::<MyPrefixSeq>::new(vec![10, 20, 0, 31, 0, 40], 1, extensions).is_valid());
+    assert!(!Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 0, 40], 1, None).is_valid());
+    assert!(!Key::<MyPrefixSeq>::new(vec![10, 20], 0, extensions).is_valid());
+  }
+
+  #[test]
+  fn key_is_prefix_of_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+    let root = key_seq.create_key([]);
+    let parent = key_seq.create_key([30]);
+    let child = key_seq.create_key([30, 40]);
+    let other = key_seq.create_key([31, 40]);
+
+    assert!(root.is_prefix_of(&parent));
+    assert!(parent.is_prefix_of(&child));
+    assert!(!child.is_prefix_of(&parent));
+    assert!(!parent.is_prefix_of(&other));
+    assert!(!child.is_prefix_of(&child.clone()));
+  }
+
+  #[test]
+  fn key_truncate_key_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut key = key_seq.create_key([40, 50, 60]);
+
+    key.truncate_key(2);
+    assert_eq!(key.get_key(), &[40, 50]);
+    assert_eq!(key.get_prefix(), &[10, 20, 30]);
+
+    key.truncate_key(0);
+    assert_eq!(key.get_key(), &[] as &[u8]);
+    assert_eq!(key.as_ref(), &[10, 20, 30]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> ExtensionPart1[30] -> Key=[]",
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "new key length 3 is greater than key length 2")]
+  fn key_truncate_key_longer_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+    let mut key = key_seq.create_key([30, 40]);
+
+    key.truncate_key(3);
+  }
+
+  #[test]
+  fn key_rebuild_with_key_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key = key_seq.create_key([40, 50, 60]);
+    let ptr = key.as_ref().as_ptr();
+
+    let key = key.rebuild_with_key([70]);
+
+    assert_eq!(key.as_ref().as_ptr(), ptr);
+    assert_eq!(key.get_key(), &[70]);
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 30, 0]);
+    assert!(key.is_valid());
+
+    let key = key.rebuild_with_key([80, 90]);
+
+    assert_eq!(key.to_vec(), key_seq.create_key([80, 90]).to_vec());
+
+    let key = key_seq.create_key_opt(None::<&[u8]>).rebuild_with_key([40]);
+
+    assert_eq!(
+      format!("{:?}", key),
+      format!("{:?}", key_seq.create_key([40]))
+    );
+  }
+
+  #[test]
+  fn key_set_key_bytes_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new();
+    let mut key = key_seq.create_key(0u64.to_be_bytes());
+    let capacity = key.bytes.capacity();
+    let ptr = key.bytes().as_ptr();
+
+    for counter in 1..=1000u64 {
+      key.set_key_bytes(&counter.to_be_bytes());
+    }
+
+    assert_eq!(key.bytes.capacity(), capacity);
+    assert_eq!(key.bytes().as_ptr(), ptr);
+    assert_eq!(key.get_key(), &1000u64.to_be_bytes());
+    assert_eq!(key.get_prefix(), &[10, 20, 0]);
+
+    key.set_key_bytes(&[30]);
+
+    assert_eq!(key.get_key(), &[30]);
+    assert_eq!(key.bytes(), &[10, 20, 0, 30]);
+
+    key.set_key_bytes(&[40, 50, 60]);
+
+    assert_eq!(key.get_key(), &[40, 50, 60]);
+    assert!(key.is_valid());
+
+    let mut key = key_seq.create_key_opt(None::<&[u8]>);
+    key.set_key_bytes(&[]);
+
+    assert_eq!(format!("{:?}", key), "KeyPart1[10, 20] -> [0] -> Key=[]");
+  }
+
+  #[test]
+  fn key_split_off_key_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut key = key_seq.create_key([40, 50]);
+
+    assert_eq!(key.split_off_key(), vec![40, 50]);
+    assert_eq!(key.get_prefix(), &[10, 20, 30]);
+    assert_eq!(key.get_key(), &[] as &[u8]);
+    assert_eq!(key.split_off_key(), Vec::<u8>::new());
+    assert_eq!(key.get_prefix(), &[10, 20, 30]);
+  }
+
+  #[test]
+  fn key_split_key_at_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([30, 31, 40, 50, 51]);
+
+    let expected: Vec<&[u8]> = vec![&[30, 31, 40, 50, 51]];
+    assert_eq!(key.split_key_at(&[]), expected);
+
+    let expected: Vec<&[u8]> = vec![&[], &[30, 31], &[40, 50, 51], &[]];
+    assert_eq!(key.split_key_at(&[0, 2, 5]), expected);
+  }
+
+  #[test]
+  fn key_as_parts_bytes_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([50, 60]);
+
+    let expected: (&[u8], &[u8], &[u8]) = (&[10, 20, 0, 30, 0], &[], &[50, 60]);
+    assert_eq!(key.as_parts_bytes(), Some(expected));
+
+    let key_seq = key_seq
+      .extend("ExtensionPart1", [40])
+      .extend("ExtensionPart2", [41, 42]);
+    let key = key_seq.create_key([]);
+
+    let expected: (&[u8], &[u8], &[u8]) = (&[10, 20, 0, 30, 0], &[40, 0, 41, 42, 0], &[]);
+    assert_eq!(key.as_parts_bytes(), Some(expected));
+
+    let key_seq = DynamicSeq::new().extend("ExtensionPart1", [40]);
+    let key = key_seq.create_key([50]);
+
+    let expected: (&[u8], &[u8], &[u8]) = (&[], &[40], &[50]);
+    assert_eq!(key.as_parts_bytes(), Some(expected));
+
+    let truncated_key = Key::<MyPrefixSeq>::new(vec![10, 20, 0], 0, None);
+    assert_eq!(truncated_key.as_parts_bytes(), None);
+
+    let truncated_key = Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 0], 6, None);
+    assert_eq!(truncated_key.as_parts_bytes(), None);
+  }
+
+  #[test]
+  fn key_split_at_part_test() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_part!(Photos, &[30]);
+    define_key_seq!(MyPrefixSeq, [Users, Photos], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("UserId", [40, 41]);
+    let key = key_seq.create_key([50]);
+
+    let expected: (&[u8], &[u8]) = (&[], &[10, 20, 0, 30, 0, 40, 41, 0, 50]);
+    assert_eq!(key.split_at_part("Users"), Some(expected));
+
+    let expected: (&[u8], &[u8]) = (&[10, 20, 0], &[30, 0, 40, 41, 0, 50]);
+    assert_eq!(key.split_at_part("Photos"), Some(expected));
+
+    let expected: (&[u8], &[u8]) = (&[10, 20, 0, 30, 0], &[40, 41, 0, 50]);
+    assert_eq!(key.split_at_part("UserId"), Some(expected));
+
+    assert_eq!(key.split_at_part("Key"), None);
+  }
+
+  #[test]
+  fn key_diff_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new();
+    let key = key_seq.create_key([50, 60]);
+
+    // Differs in a static part
+    let static_diff = Key::<MyPrefixSeq>::new(vec![10, 20, 0, 30, 41, 0, 50, 60], 2, None);
+    assert_eq!(key.diff(&static_diff), Some(4));
+    assert_eq!(
+      key.diff_report(&static_diff),
+      "keys differ at byte 4 in KeyPart2: [30, 40] != [30, 41]"
+    );
+
+    // Differs in the key
+    let key_diff = key_seq.create_key([50, 61]);
+    assert_eq!(key.diff(&key_diff), Some(7));
+    assert_eq!(
+      key.diff_report(&key_diff),
+      "keys differ at byte 7 in Key: [50, 60] != [50, 61]"
+    );
+
+    // Differs in a separator
+    let sep_diff = Key::<MyPrefixSeq>::new(vec![10, 20, 1, 30, 40, 0, 50, 60], 2, None);
+    assert_eq!(
+      key.diff_report(&sep_diff),
+      "keys differ at byte 2 in separator after KeyPart1: [0] != [1]"
+    );
+
+    // Differs in length
+    let short_key = key_seq.create_key([50]);
+    assert_eq!(key.diff(&short_key), Some(7));
+    assert_eq!(
+      key.diff_report(&short_key),
+      "keys differ at byte 7 in Key: [50, 60] != [50]"
+    );
+
+    assert_eq!(key.diff(&key_seq.create_key([50, 60])), None);
+    assert_eq!(
+      key.diff_report(&key_seq.create_key([50, 60])),
+      "keys are equal"
+    );
+  }
+
+  #[test]
+  fn key_hash_with_test() {
+    use std::hash::Hasher;
+
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut hasher = hash::Fnv1a::new();
+    let mut bytes_hasher = hash::Fnv1a::new();
+
+    key_seq.create_key([40]).hash_with(&mut hasher);
+    bytes_hasher.write(&[10, 20, 30, 40]);
+
+    assert_eq!(hasher.finish(), bytes_hasher.finish());
+  }
+
+  #[cfg(feature = "xxhash")]
+  #[test]
+  fn key_hash64_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+
+    assert_eq!(
+      key_seq.create_key([30]).hash64(),
+      xxhash_rust::xxh3::xxh3_64(&[10, 20, 30])
+    );
+    assert_ne!(
+      key_seq.create_key([30]).hash64(),
+      key_seq.create_key([31]).hash64()
+    );
+  }
+
+  #[test]
+  fn key_try_into_array_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = MyPrefixSeq::new();
+
+    let array: Result<[u8; 6], _> = key_seq.create_key([50, 60]).try_into();
+    assert_eq!(array, Ok([10, 20, 30, 40, 50, 60]));
+
+    let array: Result<[u8; 5], _> = key_seq.create_key([50, 60]).try_into();
+    assert_eq!(
+      array,
+      Err(Error::LengthMismatch {
+        len: 6,
+        expected: 5
+      })
+    );
+  }
+
+  #[test]
+  fn key_into_owned_with_seq_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+
+    let (key, owned_key) = {
+      let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [50]);
+      let key = key_seq.create_key([60, 70]);
+
+      (
+        (format!("{:?}", key), format!("{:#?}", key)),
+        key.into_owned_with_seq(),
+      )
+    };
+
+    let owned_key = std::thread::spawn(move || owned_key).join().unwrap();
+
+    assert_eq!(format!("{:?}", owned_key), key.0);
+    assert_eq!(format!("{:#?}", owned_key), key.1);
+    assert_eq!(owned_key.get_prefix(), &[10, 20, 0, 30, 40, 0, 50, 0]);
+    assert_eq!(owned_key.get_key(), &[60, 70]);
+
+    let key_seq = MyPrefixSeq::new();
+    let owned_key = key_seq
+      .create_key_opt(None::<[u8; 0]>)
+      .into_owned_with_seq();
+
+    assert_eq!(
+      format!("{:?}", owned_key),
+      "KeyPart1[10, 20] -> [0] -> KeyPart2[30, 40] -> [0]",
+    );
+    assert_eq!(Vec::from(owned_key), vec![10, 20, 0, 30, 40, 0]);
+  }
+
+  #[test]
+  fn key_replace_prefix_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(OldPrefixSeq, [KeyPart1]);
+    define_key_seq!(NewPrefixSeq, [KeyPart2]);
+
+    let old_key_seq = OldPrefixSeq::new();
+    let new_key_seq = NewPrefixSeq::new().extend("ExtensionPart1", [50]);
+    let key = old_key_seq.create_key([70, 80]);
+    let key: Key<NewPrefixSeq> = key.replace_prefix(&new_key_seq);
+
+    assert_eq!(key.get_prefix(), &[30, 40, 50]);
+    assert_eq!(key.get_key(), &[70, 80]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart2[30, 40] -> ExtensionPart1[50] -> Key=[70, 80]",
+    );
+  }
+
+  #[test]
+  fn same_layout_test() {
+    mod v1 {
+      use crate::*;
+      define_key_part!(KeyPart1, &[10, 20]);
+      define_key_part!(KeyPart2, &[30, 40]);
+      define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    }
+
+    mod v2 {
+      use crate::*;
+      define_key_part!(KeyPart1, &[10, 20]);
+      define_key_part!(KeyPart2, &[30, 41]);
+      define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    }
+
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    assert!(same_layout::<MyPrefixSeq, v1::MyPrefixSeq>());
+    assert!(!same_layout::<MyPrefixSeq, v2::MyPrefixSeq>());
+    assert!(!same_layout::<v1::MyPrefixSeq, v2::MyPrefixSeq>());
+  }
+
+  #[test]
+  fn key_seq_static_prefix() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+    define_key_seq!(OtherPrefixSeq, [KeyPart2]);
+
+    assert_eq!(MyPrefixSeq::static_prefix(), &[10, 20, 0, 30, 0]);
+    assert_eq!(
+      MyPrefixSeq::static_prefix().as_ptr(),
+      MyPrefixSeq::static_prefix().as_ptr()
+    );
+    assert_eq!(OtherPrefixSeq::static_prefix(), &[30]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [40]);
+
+    assert_eq!(
+      key_seq.create_key([50]).to_vec(),
+      vec![10, 20, 0, 30, 0, 40, 0, 50]
+    );
+  }
+
+  #[test]
+  fn key_seq_separator_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0, 1]);
+    define_key_seq!(MyOtherPrefixSeq, [KeyPart1, KeyPart2]);
+
+    assert_eq!(MyPrefixSeq::PREFIX_LEN, 8);
+    assert_eq!(MyPrefixSeq::OFFSETS, &[(0, 2), (4, 2)]);
+    assert!(!same_layout::<MyPrefixSeq, MyOtherPrefixSeq>());
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [50]);
+
+    assert_eq!(
+      format!("{:#?}", key_seq),
+      "KeyPart1[10, 20]\n  └ [0, 1]\n    └ KeyPart2[30, 40]\n      └ [0, 1]\n        └ ExtensionPart1[50]\n          └ [0, 1]",
+    );
+
+    let key = key_seq.create_key([60, 70]);
+
+    assert_eq!(
+      key.as_ref(),
+      &[10, 20, 0, 1, 30, 40, 0, 1, 50, 0, 1, 60, 70]
+    );
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 1, 30, 40, 0, 1, 50, 0, 1]);
+    assert_eq!(key.get_key(), &[60, 70]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> [0, 1] -> KeyPart2[30, 40] -> [0, 1] -> ExtensionPart1[50] -> [0, 1] -> Key=[60, 70]",
+    );
+    assert_eq!(
+      key.to_json(),
+      r#"{"parts":[{"name":"KeyPart1","bytes":[10,20]},{"name":"KeyPart2","bytes":[30,40]},{"name":"ExtensionPart1","bytes":[50]}],"separator":[0,1],"key":[60,70]}"#,
+    );
+  }
+
+  #[test]
+  fn key_seq_debug() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    assert_eq!(
+      format!("{:?}", MyPrefixSeq::new()),
+      "KeyPart1[10, 20] -> KeyPart2[30, 40]".to_string(),
+    );
+  }
+
+  #[test]
+  fn key_seq_debug_ascii() {
+    define_key_part!(Users, b"users");
+    define_key_seq!(MyPrefixSeq, [Users], sep = b"/");
+
+    let key_seq = MyPrefixSeq::new().extend("Tag", b"a\"b\\\x7f");
+    let key = key_seq.create_key(b"\x00id\xff");
+
+    assert_eq!(
+      format!("{:?}", key.debug_ascii()),
+      r#"Users["users"] -> ["/"] -> Tag["a\"b\\"\x7f] -> ["/"] -> Key=[\x00"id"\xff]"#
+    );
+    assert_eq!(
+      format!("{:?}", key),
+      "Users[117, 115, 101, 114, 115] -> [47] -> Tag[97, 34, 98, 92, 127] -> [47] -> Key=[0, 105, 100, 255]"
+    );
+    assert_eq!(
+      format!("{:?}", key_seq.create_key_opt(None::<&[u8]>).debug_ascii()),
+      r#"Users["users"] -> ["/"] -> Tag["a\"b\\"\x7f] -> ["/"]"#
+    );
+  }
+
+  #[test]
+  fn key_seq_pretty_debug() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_part!(KeyPart3, &[50, 60]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2, KeyPart3]);
+
+    assert_eq!(
+      format!("{:#?}", MyPrefixSeq::new()),
+      "KeyPart1[10, 20]\n  └ KeyPart2[30, 40]\n    └ KeyPart3[50, 60]",
+    );
+  }
+
+  #[test]
+  fn key_seq_extend() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = MyPrefixSeq::new()
+      .extend("ExtensionPart1", [50, 60])
+      .extend("ExtensionPart2", [70, 80]);
+
+    assert_eq!(
+      format!("{:#?}", key_seq),
+      "KeyPart1[10, 20]\n  └ KeyPart2[30, 40]\n    └ ExtensionPart1[50, 60]\n      └ ExtensionPart2[70, 80]",
+    );
+
+    let key = key_seq.create_key([90, 100]);
+
+    assert_eq!(key.as_ref(), &[10, 20, 30, 40, 50, 60, 70, 80, 90, 100],);
+
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> KeyPart2[30, 40] -> ExtensionPart1[50, 60] -> ExtensionPart2[70, 80] -> Key=[90, 100]",
+    );
+  }
+
+  #[test]
+  fn key_seq_extend_distinct_name() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [Users]);
+
+    let key_seq = MyPrefixSeq::new()
+      .extend("Groups", [30])
+      .extend_owned("Roles", vec![40]);
+
+    assert_eq!(key_seq.to_vec(), vec![10, 20, 30, 40]);
+  }
+
+  #[cfg(debug_assertions)]
+  #[test]
+  #[should_panic(expected = "extension \"Users\" shadows a static part of MyPrefixSeq")]
+  fn key_seq_extend_shadowing_static_part() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [Users]);
+
+    MyPrefixSeq::new().extend("Users", [30]);
+  }
+
+  #[test]
+  fn key_seq_extensions_iter() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+
+    assert_eq!(key_seq.extensions_iter().count(), 0);
+
+    let key_seq = key_seq
+      .extend("ExtensionPart1", [30, 40])
+      .extend("ExtensionPart2", [50]);
+    let extensions: Vec<(&str, &[u8])> = key_seq.extensions_iter().collect();
+
+    assert_eq!(
+      extensions,
+      vec![
+        ("ExtensionPart1", &[30, 40][..]),
+        ("ExtensionPart2", &[50][..]),
+      ],
+    );
+  }
+
+  #[test]
+  fn key_seq_extend_owned() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let bytes = vec![30, 40];
+    let bytes_ptr = bytes.as_ptr();
+    let key_seq = MyPrefixSeq::new()
+      .extend_owned("ExtensionPart1", bytes)
+      .extend("ExtensionPart2", [50]);
+
+    assert_eq!(key_seq.get_extensions().unwrap()[0].1.as_ptr(), bytes_ptr);
+    assert_eq!(
+      format!("{:?}", key_seq.create_key([60])),
+      "KeyPart1[10, 20] -> ExtensionPart1[30, 40] -> ExtensionPart2[50] -> Key=[60]",
+    );
+  }
+
+  #[test]
+  fn key_seq_extend_ordered() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key = |category: u8, timestamp: u16| {
+      MyPrefixSeq::new()
+        .extend_ordered("Category", [category], Order::Ascending)
+        .extend_ordered("Timestamp", timestamp.to_be_bytes(), Order::Descending)
+        .to_vec()
+    };
+
+    let mut keys = vec![
+      key(2, 100),
+      key(1, 100),
+      key(1, 300),
+      key(2, 500),
+      key(1, 200),
+    ];
+    keys.sort();
+
+    assert_eq!(
+      keys,
+      vec![
+        key(1, 300),
+        key(1, 200),
+        key(1, 100),
+        key(2, 500),
+        key(2, 100)
+      ],
+    );
+    assert_eq!(key(1, 1), vec![10, 20, 1, 255, 254]);
+  }
+
+  #[test]
+  fn key_seq_inclusive_end() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let end = key_seq.inclusive_end([40, 50]);
+
+    assert_eq!(end.len(), 5 + INCLUSIVE_END_FILL_LEN);
+    assert_eq!(&end[..5], &[10, 20, 30, 40, 50]);
+    assert!(end[5..].iter().all(|byte| *byte == 0xFF));
+
+    let inside = key_seq.create_key([40, 50, 0xFF, 0xFF]).to_vec();
+    let outside = key_seq.create_key([40, 51]).to_vec();
+
+    assert!(inside <= end);
+    assert!(key_seq.create_key([40, 50]).to_vec() <= end);
+    assert!(outside > end);
+  }
+
+  #[test]
+  fn key_seq_key_after() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let prefix = key_seq.create_key([]).to_vec();
+    let last_seen = key_seq.create_key([40, 0xFF]).to_vec();
+    let after = key_seq.key_after([40, 0xFF]);
+
+    assert_eq!(after, vec![10, 20, 0, 30, 0, 40, 0xFF, 0]);
+    assert!(after > last_seen);
+    assert!(after <= key_seq.create_key([40, 0xFF, 0]).to_vec());
+    assert!(after < key_seq.create_key([41]).to_vec());
+    assert!(after.starts_with(&prefix));
+
+    let after = key_seq.key_after([]);
+
+    assert_eq!(after, vec![10, 20, 0, 30, 0, 0]);
+    assert!(after > prefix);
+    assert!(after.starts_with(&prefix));
+  }
+
+  #[test]
+  fn util_next_prefix_test() {
+    use util::next_prefix;
+
+    assert_eq!(next_prefix(&[]), None);
+    assert_eq!(next_prefix(&[0xFF]), None);
+    assert_eq!(next_prefix(&[0xFF, 0xFF, 0xFF]), None);
+    assert_eq!(next_prefix(&[0x00]), Some(vec![0x01]));
+    assert_eq!(next_prefix(&[0x00, 0xFF]), Some(vec![0x01]));
+    assert_eq!(next_prefix(&[0xFF, 0x00]), Some(vec![0xFF, 0x01]));
+    assert_eq!(next_prefix(&[10, 0xFE]), Some(vec![10, 0xFF]));
+    assert_eq!(next_prefix(&[10, 0xFE, 0xFF, 0xFF]), Some(vec![10, 0xFF]));
+
+    let prefix = [10, 0xFF];
+    let next = next_prefix(&prefix).unwrap();
+
+    for tail in [&[][..], &[0], &[0xFF; 8]] {
+      let key = [&prefix[..], tail].concat();
+
+      assert!(key < next);
+    }
+  }
+
+  #[test]
+  fn key_seq_reverse_seek() {
+    define_key_part!(KeyPart1, &[10, 0xFF]);
+    define_key_part!(KeyPart2, &[0xFF, 0xFF]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+    define_key_seq!(MaxPrefixSeq, [KeyPart2]);
+
+    fn scan(keys: &[Vec<u8>], start: Option<Vec<u8>>, end: Vec<u8>) -> Vec<&Vec<u8>> {
+      keys
+        .iter()
+        .rev()
+        .filter(|key| start.as_ref().is_none_or(|start| *key < start) && **key >= end)
+        .collect()
+    }
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let start = key_seq.reverse_seek_start();
+    let end = key_seq.reverse_seek_end();
+
+    assert_eq!(start, Some(vec![10, 0xFF, 31]));
+    assert_eq!(end, vec![10, 0xFF, 30]);
+
+    let mut keys = [
+      vec![10, 0xFF, 29, 0xFF],
+      key_seq.create_key([]).to_vec(),
+      key_seq.create_key([0]).to_vec(),
+      key_seq.create_key([0xFF, 0xFF]).to_vec(),
+      vec![10, 0xFF, 31],
+    ];
+    keys.sort();
+
+    let scanned = scan(&keys, start, end.clone());
+
+    assert_eq!(scanned.len(), 3);
+    assert!(scanned.iter().all(|key| key.starts_with(&end)));
+    assert_eq!(MyPrefixSeq::new().reverse_seek_start(), Some(vec![11]));
+
+    let key_seq = MaxPrefixSeq::new();
+
+    assert_eq!(key_seq.reverse_seek_start(), None);
+    assert_eq!(key_seq.reverse_seek_end(), vec![0xFF, 0xFF]);
+
+    let mut keys = [
+      vec![0xFF, 0xFE, 0xFF],
+      key_seq.create_key([]).to_vec(),
+      key_seq.create_key([0]).to_vec(),
+      key_seq.create_key([0xFF, 0xFF]).to_vec(),
+    ];
+    keys.sort();
+
+    let scanned = scan(
+      &keys,
+      key_seq.reverse_seek_start(),
+      key_seq.reverse_seek_end(),
+    );
+
+    assert_eq!(
+      scanned,
+      vec![
+        &vec![0xFF, 0xFF, 0xFF, 0xFF],
+        &vec![0xFF, 0xFF, 0],
+        &vec![0xFF, 0xFF]
+      ],
+    );
+
+    let key_seq = DynamicSeq::new();
+
+    assert_eq!(key_seq.reverse_seek_start(), None);
+    assert_eq!(key_seq.reverse_seek_end(), Vec::<u8>::new());
+    assert_eq!(
+      scan(
+        &keys,
+        key_seq.reverse_seek_start(),
+        key_seq.reverse_seek_end()
+      )
+      .len(),
+      keys.len(),
+    );
+  }
+
+  #[test]
+  fn key_seq_parent_prefix() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_part!(Photos, &[30]);
+    define_key_seq!(MyPrefixSeq, [Users, Photos]);
+    define_key_seq!(MySepPrefixSeq, [Users, Photos], sep = &[0]);
+    define_key_seq!(UsersSeq, [Users]);
+
+    let key_seq = MyPrefixSeq::new();
+
+    assert_eq!(key_seq.parent_prefix(), vec![10, 20]);
+    assert_eq!(
+      key_seq
+        .clone()
+        .extend("UserId", [40, 41])
+        .extend("PhotoId", [50])
+        .parent_prefix(),
+      vec![10, 20, 30, 40, 41]
+    );
+    assert_eq!(UsersSeq::new().parent_prefix(), Vec::<u8>::new());
+
+    let key_seq = MySepPrefixSeq::new();
+
+    assert_eq!(key_seq.parent_prefix(), vec![10, 20, 0]);
+    assert_eq!(
+      key_seq.extend("PhotoId", [50]).parent_prefix(),
+      vec![10, 20, 0, 30, 0]
+    );
+
+    assert_eq!(DynamicSeq::new().parent_prefix(), Vec::<u8>::new());
+  }
+
+  #[test]
+  fn key_seq_name() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+    define_key_seq!(pub(crate) OtherPrefixSeq, [KeyPart1], sep = &[0]);
+
+    fn label<T: KeyPartsSequence>(seq: &T) -> String {
+      format!("{}:{}", seq.seq_name(), seq.get_prefix_len())
+    }
+
+    assert_eq!(MyPrefixSeq::NAME, "MyPrefixSeq");
+    assert_eq!(
+      label(&MyPrefixSeq::new().extend("Ext", [30])),
+      "MyPrefixSeq:3"
+    );
+    assert_eq!(OtherPrefixSeq::new().seq_name(), "OtherPrefixSeq");
+    assert_eq!(DynamicSeq::new().seq_name(), "DynamicSeq");
+  }
+
+  #[test]
+  fn key_seq_intermediate_prefixes() {
+    define_key_part!(Users, &[10, 11]);
+    define_key_part!(Profiles, &[20]);
+    define_key_part!(Photos, &[30, 31]);
+    define_key_seq!(MyPrefixSeq, [Users, Profiles, Photos]);
+    define_key_seq!(MySepPrefixSeq, [Users, Profiles], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new();
+    let prefixes = key_seq.intermediate_prefixes();
+
+    assert_eq!(
+      prefixes,
+      vec![vec![10, 11], vec![10, 11, 20], vec![10, 11, 20, 30, 31]]
+    );
+    assert_eq!(prefixes.last(), Some(&key_seq.to_vec()));
+
+    let key_seq = MySepPrefixSeq::new().extend("UserId", [40]);
+
+    assert_eq!(
+      key_seq.intermediate_prefixes(),
+      vec![
+        vec![10, 11, 0],
+        vec![10, 11, 0, 20, 0],
+        vec![10, 11, 0, 20, 0, 40, 0]
+      ]
+    );
+    assert!(DynamicSeq::new().intermediate_prefixes().is_empty());
+  }
+
+  #[test]
+  fn key_seq_is_unambiguously_parseable() {
+    define_key_part!(KeyPart1, &[10, 0]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+    define_key_seq!(MySepPrefixSeq, [KeyPart1], sep = &[0, 0]);
+
+    assert!(MyPrefixSeq::new().is_unambiguously_parseable());
+    assert!(!MyPrefixSeq::new()
+      .extend_u64("Id", 1, Endianness::Big)
+      .is_unambiguously_parseable());
+
+    assert!(MySepPrefixSeq::new().is_unambiguously_parseable());
+    assert!(MySepPrefixSeq::new()
+      .extend("Name", b"users")
+      .extend("Id", [0, 1, 0, 1])
+      .is_unambiguously_parseable());
+    assert!(!MySepPrefixSeq::new()
+      .extend("Id", [0, 1, 0])
+      .is_unambiguously_parseable());
+    assert!(!MySepPrefixSeq::new()
+      .extend("Name", b"users")
+      .extend("Id", [1, 0, 0, 1])
+      .is_unambiguously_parseable());
+
+    assert!(DynamicSeq::new().is_unambiguously_parseable());
+    assert!(
+      DynamicSeq::from_parts(vec![Part::new_dynamic("Name", b"users".to_vec())])
+        .is_unambiguously_parseable()
+    );
+    assert!(!DynamicSeq::new()
+      .extend("Name", b"users")
+      .is_unambiguously_parseable());
+  }
+
+  #[test]
+  fn key_seq_common_prefix_len() {
+    define_key_part!(Tenants, &[10, 20]);
+    define_key_part!(Users, &[30, 40]);
+    define_key_part!(Groups, &[30, 50]);
+    define_key_seq!(UsersSeq, [Tenants, Users]);
+    define_key_seq!(GroupsSeq, [Tenants, Groups]);
+    define_key_seq!(UsersSepSeq, [Tenants, Users], sep = &[0]);
+
+    let users_seq = UsersSeq::new();
+    let groups_seq = GroupsSeq::new();
+
+    assert_eq!(users_seq.common_prefix_len(&groups_seq), 3);
+    assert_eq!(groups_seq.common_prefix_len(&users_seq), 3);
+    assert_eq!(users_seq.common_prefix_len(&UsersSepSeq::new()), 2);
+    assert_eq!(users_seq.common_prefix_len(&users_seq), 4);
+    assert_eq!(
+      users_seq.common_prefix_len(&users_seq.clone().extend("Id", [60])),
+      4
+    );
+    assert_eq!(users_seq.common_prefix_len(&DynamicSeq::new()), 0);
+  }
+
+  #[test]
+  fn key_seq_layout_hash() {
+    mod v1 {
+      use crate::*;
+      define_key_part!(KeyPart1, &[10, 20]);
+      define_key_part!(KeyPart2, &[30, 40]);
+      define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    }
+
+    mod v2 {
+      use crate::*;
+      define_key_part!(KeyPart1, &[10, 20]);
+      define_key_part!(KeyPart2, &[30, 41]);
+      define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    }
+
+    mod v3 {
+      use crate::*;
+      define_key_part!(KeyPart1, &[10, 20]);
+      define_key_part!(KeyPart2, &[30, 40]);
+      define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+    }
+
+    let hash = v1::MyPrefixSeq::new().layout_hash();
+
+    assert_eq!(hash, 0x3819_69b2_87f5_40f8);
+    assert_eq!(
+      hash,
+      v1::MyPrefixSeq::new()
+        .extend("ExtensionPart1", [50])
+        .layout_hash()
+    );
+    assert_ne!(hash, v2::MyPrefixSeq::new().layout_hash());
+    assert_ne!(hash, v3::MyPrefixSeq::new().layout_hash());
+  }
+
+  #[test]
+  fn key_seq_extend_numbers() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new()
+      .extend_u16("U16", 0x0102, Endianness::default())
+      .extend_u32("U32", 0x0102_0304, Endianness::Little)
+      .extend_u64("U64", 1, Endianness::Big)
+      .extend_u128("U128", 1, Endianness::Little);
+
+    let mut expected = vec![10, 20, 1, 2, 4, 3, 2, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+    expected.extend_from_slice(&[0; 15]);
+
+    assert_eq!(key_seq.to_vec(), expected);
+
+    let big = |value: u64| {
+      MyPrefixSeq::new()
+        .extend_u64("U64", value, Endianness::Big)
+        .to_vec()
+    };
+    let little = |value: u64| {
+      MyPrefixSeq::new()
+        .extend_u64("U64", value, Endianness::Little)
+        .to_vec()
+    };
+
+    assert!(big(255) < big(256));
+    assert!(little(255) > little(256));
+  }
+
+  #[test]
+  fn key_seq_extend_bool_and_u8() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[1]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new()
+      .extend_bool("Active", true)
+      .extend_bool("Deleted", false)
+      .extend_u8("Kind", 7)
+      .extend_u8("Zero", 0);
+    let key = key_seq.create_key([30]);
+
+    assert_eq!(key.bytes(), &[10, 20, 0, 1, 0, 1, 0, 0, 0, 7, 0, 0, 0, 30]);
+
+    assert_eq!(key.get_bool("Active"), Some(true));
+    assert_eq!(key.get_bool("Deleted"), Some(false));
+    assert_eq!(key.get_bool("Kind"), None);
+    assert_eq!(key.get_bool("Zero"), Some(false));
+    assert_eq!(key.get_bool("KeyPart2"), Some(true));
+
+    assert_eq!(key.get_u8("Kind"), Some(7));
+    assert_eq!(key.get_u8("Active"), Some(1));
+    assert_eq!(key.get_u8("KeyPart1"), None);
+    assert_eq!(key.get_u8("Missing"), None);
+  }
+
+  #[test]
+  fn key_seq_key_space() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new();
+
+    assert_eq!(key_seq.key_space(0), 1);
+    assert_eq!(key_seq.key_space(1), 256);
+    assert_eq!(key_seq.key_space(8), u64::MAX as u128 + 1);
+    assert_eq!(key_seq.key_space(15), 1 << 120);
+    assert_eq!(key_seq.key_space(16), u128::MAX);
+    assert_eq!(key_seq.key_space(usize::MAX), u128::MAX);
+  }
+
+  #[test]
+  fn key_seq_to_json() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = MyPrefixSeq::new().extend("Extension\"Part\"\n", [50]);
+
+    assert_eq!(
+      key_seq.to_json(),
+      r#"{"parts":[{"name":"KeyPart1","bytes":[10,20]},{"name":"KeyPart2","bytes":[30,40]},{"name":"Extension\"Part\"\n","bytes":[50]}]}"#,
+    );
+    assert_eq!(
+      key_seq.create_key([]).to_json(),
+      r#"{"parts":[{"name":"KeyPart1","bytes":[10,20]},{"name":"KeyPart2","bytes":[30,40]},{"name":"Extension\"Part\"\n","bytes":[50]}],"key":[]}"#,
+    );
+    assert_eq!(
+      key_seq.create_key([0, 255]).to_json(),
+      r#"{"parts":[{"name":"KeyPart1","bytes":[10,20]},{"name":"KeyPart2","bytes":[30,40]},{"name":"Extension\"Part\"\n","bytes":[50]}],"key":[0,255]}"#,
+    );
+  }
+
+  #[test]
+  fn key_seq_render_template() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_seq!(MySepPrefixSeq, [KeyPart1], sep = &[0, 1]);
+
+    assert_eq!(
+      MyPrefixSeq::new()
+        .extend("UserId", [40, 50, 60])
+        .render_template(),
+      "KeyPart1(2 bytes) | KeyPart2(1 byte) | UserId(3 bytes) | <key>"
+    );
+    assert_eq!(
+      MySepPrefixSeq::new().render_template(),
+      "KeyPart1(2 bytes) | sep(2 bytes) | <key>"
+    );
+    assert_eq!(DynamicSeq::new().render_template(), "<key>");
+  }
+
+  #[test]
+  fn dynamic_seq_test() {
+    let key_seq = DynamicSeq::from_parts(vec![
+      Part::new_dynamic("KeyPart1", vec![10, 20]),
+      Part::new_dynamic("KeyPart2", vec![30, 40]),
+    ])
+    .extend("ExtensionPart1", [50]);
+
+    assert_eq!(
+      format!("{:#?}", key_seq),
+      "KeyPart1[10, 20]\n  └ KeyPart2[30, 40]\n    └ ExtensionPart1[50]",
+    );
+
+    let key = key_seq.create_key([60, 70]);
+
+    assert_eq!(key.get_prefix(), &[10, 20, 30, 40, 50]);
+    assert_eq!(key.get_key(), &[60, 70]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> KeyPart2[30, 40] -> ExtensionPart1[50] -> Key=[60, 70]",
+    );
+
+    let expected: (&[u8], &[u8], &[u8]) = (&[10, 20, 30, 40], &[50], &[60, 70]);
+    assert_eq!(key.as_parts_bytes(), Some(expected));
+    assert_eq!(
+      key_seq.extensions_iter().collect::<Vec<_>>(),
+      vec![("ExtensionPart1", &[50][..])],
+    );
+    assert_eq!(
+      key_seq.get_layout_parts(),
+      vec![("KeyPart1", &[10, 20][..]), ("KeyPart2", &[30, 40][..])],
+    );
+  }
+
+  #[test]
+  fn dynamic_seq_layout_hash_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let key_seq = DynamicSeq::from_parts(vec![
+      Part::new_dynamic("KeyPart1", vec![10, 20]),
+      Part::new_dynamic("KeyPart2", vec![30, 40]),
+    ]);
+    let other_bytes_seq = DynamicSeq::from_parts(vec![
+      Part::new_dynamic("KeyPart1", vec![10, 20]),
+      Part::new_dynamic("KeyPart2", vec![30, 41]),
+    ]);
+    let other_name_seq = DynamicSeq::from_parts(vec![
+      Part::new_dynamic("KeyPart1", vec![10, 20]),
+      Part::new_dynamic("KeyPart3", vec![30, 40]),
+    ]);
+
+    assert_ne!(key_seq.layout_hash(), other_bytes_seq.layout_hash());
+    assert_ne!(key_seq.layout_hash(), other_name_seq.layout_hash());
+    assert_ne!(key_seq.layout_hash(), DynamicSeq::new().layout_hash());
+    assert_eq!(key_seq.layout_hash(), MyPrefixSeq::new().layout_hash());
+    assert_eq!(
+      key_seq.clone().extend("UserId", [1]).layout_hash(),
+      key_seq.clone().extend("UserId", [2]).layout_hash(),
+    );
+  }
+
+  #[test]
+  fn raw_builder_test() {
+    use std::io::Write;
+
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut builder = key_seq.raw_builder();
+
+    builder.write_all(&[40, 50]).unwrap();
+    builder.extend([60, 70].iter().copied());
+    builder.push_slice(&[80]);
+
+    let key = builder.finish();
+
+    assert_eq!(key.get_prefix(), &[10, 20, 30]);
+    assert_eq!(key.get_key(), &[40, 50, 60, 70, 80]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> ExtensionPart1[30] -> Key=[40, 50, 60, 70, 80]",
+    );
+  }
+
+  #[test]
+  fn fmt_builder_test() {
+    use std::fmt::Write;
+
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut builder = key_seq.fmt_builder();
+    let (kind, id) = ("user", 42);
+
+    write!(builder, "{}:{}", kind, id).unwrap();
+    builder.write_char('/').unwrap();
+    builder.write_str("ü").unwrap();
+
+    let key = builder.finish();
+
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 30, 0]);
+    assert_eq!(key.get_key_str(), Ok("user:42/ü"));
+    assert!(key.is_valid());
+    assert_eq!(
+      key.to_vec(),
+      key_seq.create_key("user:42/ü".as_bytes()).to_vec()
+    );
+  }
+
+  #[test]
+  fn key_seq_create_key_checked() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_seq!(UserById, [Users], require_key = true);
+    define_key_seq!(UserBySepId, [Users], sep = &[0], require_key = true);
+    define_key_seq!(AllUsers, [Users]);
+
+    let key_seq = UserById::new();
+
+    assert!(UserById::is_key_required());
+    assert_eq!(
+      key_seq.create_key_checked([30]).unwrap().to_vec(),
+      vec![10, 20, 30]
+    );
+    assert_eq!(key_seq.create_key_checked([]).unwrap_err(), Error::EmptyKey);
+    assert_eq!(
+      UserBySepId::new().create_key_checked([]).unwrap_err(),
+      Error::EmptyKey
+    );
+
+    assert!(!AllUsers::is_key_required());
+    assert_eq!(
+      AllUsers::new().create_key_checked([]).unwrap().to_vec(),
+      vec![10, 20]
+    );
+  }
+
+  #[test]
+  fn key_seq_create_key_ref() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key = key_seq.create_key_ref(&[40, 50]);
+
+    assert!(!key.is_heap());
+    assert_eq!(key.bytes(), key_seq.create_key([40, 50]).as_ref());
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 30, 0]);
+    assert_eq!(key.get_key(), &[40, 50]);
+
+    let long_key = [60; KEY_REF_INLINE_LEN];
+    let key = key_seq.create_key_ref(&long_key);
+
+    assert!(key.is_heap());
+    assert_eq!(key.as_ref(), key_seq.create_key(long_key).as_ref());
+    assert_eq!(key.get_key(), &long_key);
+
+    let key_len = KEY_REF_INLINE_LEN - key_seq.get_prefix_len();
+    assert!(!key_seq.create_key_ref(&long_key[..key_len]).is_heap());
+
+    let empty_seq = DynamicSeq::new();
+    let key = empty_seq.create_key_ref(&long_key);
+
+    assert!(!key.is_heap());
+    assert_eq!(key.bytes().as_ptr(), long_key.as_ptr());
+    assert_eq!(key.get_prefix(), &[] as &[u8]);
+  }
+
+  #[test]
+  fn key_seq_create_key_into() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut buf = vec![1, 2];
+
+    key_seq.create_key_into([40, 50], &mut buf);
+    key_seq.create_key_into([60], &mut buf);
+
+    assert_eq!(key_seq.get_prefix_len(), 5);
+    assert_eq!(
+      buf,
+      vec![1, 2, 10, 20, 0, 30, 0, 40, 50, 10, 20, 0, 30, 0, 60]
+    );
+  }
+
+  #[test]
+  fn key_seq_create_key_array() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
 
-/// Defines a key sequence. Each key part is a uniq struct whose implement trait [`the_key::KeyPartsSequence`][KeyPartsSequence]
-///
-/// # Example
-/// ```
-/// use the_key::*;
-///
-/// define_key_part!(KeyPart1, "key_part_1".as_bytes());
-/// define_key_part!(KeyPart2, "key_part_2".as_bytes());
-/// define_key_seq!(KeyPartsSeq, [KeyPart1, KeyPart2]);
-/// ```
-#[macro_export]
-macro_rules! define_key_seq {
-  ($name:ident, [$($key_part:ident),*]) => {
-    #[derive(Clone)]
-    pub struct $name {
-      parts: [KeyPartItem; $crate::count!($($key_part),*)],
-      extensions: Option<Vec<KeyExtensionsItem>>,
-      len: usize,
-    }
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key: [u8; 7] = key_seq.create_key_array(&[40, 50]);
 
-    impl $name {
-      pub fn new() -> Self {
-        let mut len = 0;
-        let parts: [KeyPartItem; $crate::count!($($key_part),*)] = [
-          $({
-            let key_part = $key_part::new();
-            let bytes = key_part.get_bytes();
+    assert_eq!(key, [10, 20, 0, 30, 0, 40, 50]);
+    assert_eq!(&key[..], key_seq.create_key([40, 50]).bytes());
+  }
 
-            len += bytes.len();
+  #[test]
+  #[should_panic(expected = "array length 6 is not equal to prefix length 3 plus key length 2")]
+  fn key_seq_create_key_array_wrong_len() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
 
-            (key_part.get_name(), bytes)
-          },)*
-        ];
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let _: [u8; 6] = key_seq.create_key_array(&[40, 50]);
+  }
 
-        Self {
-          len,
-          parts,
-          extensions: None,
-        }
+  #[cfg(feature = "allocator_api")]
+  #[test]
+  fn key_seq_create_key_in() {
+    use std::alloc::{AllocError, Allocator, Global, Layout};
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+
+    #[derive(Clone, Copy)]
+    struct CountingAlloc<'c>(&'c Cell<usize>);
+
+    unsafe impl<'c> Allocator for CountingAlloc<'c> {
+      fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.set(self.0.get() + 1);
+        Global.allocate(layout)
       }
 
-      // This just a public api
-      #[allow(dead_code)]
-      fn to_vec(&self) -> Vec<u8> {
-        self.create_key(&[]).to_vec()
+      unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        Global.deallocate(ptr, layout)
       }
     }
 
-    impl KeyPartsSequence for $name {
-      fn new() -> Self {
-        $name::new()
-      }
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
 
-      fn get_struct() -> Vec<KeyPartItem> {
-        let mut parts = Vec::new();
+    let allocations = Cell::new(0);
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key = key_seq.create_key_in([40, 50], CountingAlloc(&allocations));
 
-        $({
-          let key = $key_part::new();
-          parts.push((key.get_name(), key.get_bytes()));
-        })*
+    assert_eq!(key.as_ref(), key_seq.create_key([40, 50]).as_ref());
+    assert_eq!(key.get_key(), &[40, 50]);
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 30, 0]);
+    assert_eq!(
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> [0] -> ExtensionPart1[30] -> [0] -> Key=[40, 50]",
+    );
+    assert_eq!(
+      format!("{:?}", OwnedKey::from(key.clone())),
+      format!("{:?}", key),
+    );
+    assert_eq!(allocations.get(), 2);
 
-        parts
-      }
+    let other_allocations = Cell::new(0);
+    let child = key_seq.create_key_in([40, 50, 60], CountingAlloc(&other_allocations));
 
-      fn get_extensions(&self) -> Option<&[KeyExtensionsItem]> {
-        self.extensions.as_ref().map(|v| v.as_slice())
-      }
+    assert!(key.is_prefix_of(&child));
+    assert!(key.is_prefix_of(&key_seq.create_key([40, 50, 60])));
+    assert_eq!(key.diff(&child), Some(7));
+    assert_eq!(
+      key.diff_report(&child),
+      "keys differ at byte 7 in Key: [40, 50] != [40, 50, 60]",
+    );
 
-      fn extend<B: AsRef<[u8]>>(mut self, key_part_name: &'static str, bytes: B) -> Self {
-        let key_bytes = bytes.as_ref().to_vec();
-        self.len += key_bytes.len();
+    let bytes = key.into_bytes();
 
-        self.extensions = match self.extensions {
-          Some(mut extensions) => {
-            extensions.push((key_part_name, key_bytes));
+    assert_eq!(bytes.capacity(), bytes.len());
+    assert_eq!(allocations.get(), 2);
+  }
 
-            Some(extensions)
-          },
-          None => Some(vec![(key_part_name, key_bytes)]),
-        };
+  #[test]
+  fn key_seq_prefix_bytes_into() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
 
-        self
-      }
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut buf = Vec::new();
 
-      fn create_key<T: AsRef<[u8]>>(&self, key: T) -> Key<Self> {
-        let key = key.as_ref();
-        let mut result_key: Vec<u8> = Vec::with_capacity(self.len + key.len());
+    key_seq.prefix_bytes_into(&mut buf);
 
-        self.parts.iter().for_each(|(_, bytes)| {
-          result_key.extend_from_slice(bytes);
-        });
+    assert_eq!(buf, vec![10, 20, 0, 30, 0]);
+    assert_eq!(buf.capacity(), key_seq.get_prefix_len());
 
-        if let Some(extensions) = &self.extensions {
-          extensions.iter().for_each(|(_, bytes)| {
-            result_key.extend_from_slice(bytes);
-          });
-        }
+    buf.push(1);
+    key_seq.prefix_bytes_into(&mut buf);
 
-        result_key.extend_from_slice(key);
+    assert_eq!(buf, vec![10, 20, 0, 30, 0, 1, 10, 20, 0, 30, 0]);
+    assert_eq!(buf, [key_seq.to_vec(), vec![1], key_seq.to_vec()].concat());
+  }
 
-        Key::new(
-          result_key,
-          key.len(),
-          self.extensions.as_ref().map(|v| v.as_slice())
-        )
-      }
-    }
+  #[cfg(feature = "bytes")]
+  #[test]
+  fn key_seq_create_key_into_bytes() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
 
-    impl std::fmt::Debug for $name {
-      fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.fmt_debug(
-          &self.parts,
-          self.extensions.as_ref().map(|v| v.as_slice()),
-          f
-        )
-      }
-    }
-  };
-}
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let mut buf = bytes::BytesMut::from(&[1, 2][..]);
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use test::Bencher;
+    key_seq.create_key_into_bytes([40, 50], &mut buf);
+
+    assert_eq!(&buf[..], &[1, 2, 10, 20, 30, 40, 50]);
+  }
 
   #[test]
-  fn key_part_test() {
-    define_key_part!(KeyPart1, "my_key_part_1".as_bytes());
+  fn dynamic_seq_from_dyn_parts_test() {
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_part!(KeyPart2, &[30, 40]);
 
-    let kp = KeyPart1::new();
+    let parts: Vec<Box<dyn KeyPart>> = vec![Box::new(KeyPart2::new()), Box::new(KeyPart1::new())];
+    let key_seq = DynamicSeq::from_dyn_parts(&parts);
 
     assert_eq!(
-      kp.get_name(),
-      "KeyPart1",
+      format!("{:?}", key_seq.create_key([50])),
+      "KeyPart2[30, 40] -> KeyPart1[10, 20] -> Key=[50]",
     );
+  }
 
-    assert_eq!(
-      kp.get_bytes(),
-      b"my_key_part_1",
-    );
+  #[test]
+  fn exact_capacity_test() {
+    use std::io::Write;
+
+    define_key_part!(KeyPart1, &[10, 20]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
+
+    let key_seq = MyPrefixSeq::new()
+      .extend("ExtensionPart1", [30, 40, 50])
+      .extend_ordered("ExtensionPart2", [60], Order::Descending);
+
+    key_seq
+      .get_extensions()
+      .unwrap()
+      .iter()
+      .for_each(|(_, bytes)| {
+        assert_eq!(bytes.capacity(), bytes.len());
+      });
+
+    let key = key_seq.create_key([70; 16]).to_vec();
+    assert_eq!(key.capacity(), key.len());
+
+    let mut buf = Vec::new();
+    key_seq.create_key_into([70; 16], &mut buf);
+    assert_eq!(buf.capacity(), buf.len());
+
+    let end = key_seq.inclusive_end([70]);
+    assert_eq!(end.capacity(), end.len());
+
+    let mut builder = key_seq.raw_builder_with_capacity(16);
+    builder.write_all(&[70; 16]).unwrap();
+    let key = builder.finish().to_vec();
+    assert_eq!(key.capacity(), key.len());
   }
 
   #[test]
-  fn key_from_seq_test() {
+  fn key_seq_parse_key() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1], sep = &[0]);
 
+    let key_seq = MyPrefixSeq::new().extend("ExtensionPart1", [30]);
+    let key = key_seq.parse_key(&[10, 20, 0, 30, 0, 40, 50]).unwrap();
+
+    assert_eq!(key.get_prefix(), &[10, 20, 0, 30, 0]);
+    assert_eq!(key.get_key(), &[40, 50]);
     assert_eq!(
-      MyPrefixSeq::new().create_key(&[50, 60]).to_vec(),
-      vec![10, 20, 30, 40, 50, 60],
-    )
+      format!("{:?}", key),
+      "KeyPart1[10, 20] -> [0] -> ExtensionPart1[30] -> [0] -> Key=[40, 50]",
+    );
+    assert_eq!(
+      key_seq.parse_key(&[10, 20, 0, 30]).unwrap_err(),
+      Error::TooShort { len: 4, min: 5 },
+    );
+    assert_eq!(
+      key_seq.parse_key(&[10, 20, 0, 31, 0, 40]).unwrap_err(),
+      Error::PrefixMismatch {
+        expected: vec![10, 20, 0, 30, 0],
+        found: vec![10, 20, 0, 31, 0],
+      },
+    );
   }
 
   #[test]
-  fn key_get_key_test() {
+  fn key_seq_scan() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
 
     let key_seq = MyPrefixSeq::new();
-    let key = key_seq.create_key(&[70, 80]);
+    let raw: Vec<&[u8]> = vec![&[10, 20], &[10, 20, 30], &[10, 21], &[10, 20, 40]];
+    let keys: Vec<Result<Key<MyPrefixSeq>, Error>> = key_seq.scan(raw.into_iter()).collect();
 
-    let expected: &[u8] = &[70, 80];
-    assert_eq!(key.get_key(), expected);
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keys[0].as_ref().unwrap().get_key(), &[] as &[u8]);
+    assert_eq!(keys[1].as_ref().unwrap().get_key(), &[30]);
+    assert_eq!(
+      keys[2].as_ref().unwrap_err(),
+      &Error::PrefixMismatch {
+        expected: vec![10, 20],
+        found: vec![10, 21],
+      },
+    );
+
+    let raw: Vec<&[u8]> = vec![&[10, 20, 30], &[10, 20, 40]];
+
+    assert_eq!(
+      key_seq.scan(raw.into_iter()).filter(Result::is_ok).count(),
+      2
+    );
   }
 
   #[test]
-  fn key_get_prefix_test() {
+  fn concat_keys_test() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_seq!(MyPrefixSeq, [KeyPart1]);
 
     let key_seq = MyPrefixSeq::new();
-    let key = key_seq.create_key(&[70, 80]);
+    let keys = [
+      key_seq.create_key([30, 40]),
+      key_seq.create_key([50]),
+      key_seq.create_key([60, 70, 80]),
+    ];
 
-    assert_eq!(key.get_prefix(), &[10, 20, 30, 40])
+    let result = concat_keys(&keys);
+
+    assert_eq!(result, vec![10, 20, 30, 40, 10, 20, 50, 10, 20, 60, 70, 80]);
+    assert_eq!(result.capacity(), result.len());
   }
 
   #[test]
-  fn key_seq_debug() {
+  fn key_layout_test() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_layout!(MyLayout, MyLayoutFields, [KeyPart1], {
+      field1: 2,
+      field2: 1,
+      field3: 3,
+    });
 
-    assert_eq!(
-      format!("{:?}", MyPrefixSeq::new()),
-      "KeyPart1[10, 20] -> KeyPart2[30, 40]".to_string(),
-    );
+    let layout = MyLayout::new();
+    let key = layout.parse([10, 20, 30, 31, 40, 50, 51, 52]).unwrap();
+
+    assert_eq!(MyLayout::KEY_LEN, 6);
+    assert_eq!(key.field1(), Some(&[30, 31][..]));
+    assert_eq!(key.field2(), Some(&[40][..]));
+    assert_eq!(key.field3(), Some(&[50, 51, 52][..]));
+    assert_eq!(key.get_prefix(), &[10, 20]);
+
+    let key = layout.create_key([60, 61, 70, 80, 81, 82]);
+
+    assert_eq!(key.field2(), Some(&[70][..]));
   }
 
   #[test]
-  fn key_seq_pretty_debug() {
+  fn key_layout_wrong_width_test() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_part!(KeyPart3, &[50, 60]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2, KeyPart3]);
+    define_key_layout!(MyLayout, MyLayoutFields, [KeyPart1], {
+      field1: 2,
+      field2: 1,
+    });
+
+    let layout = MyLayout::new();
+    let key = layout.create_key([30]);
 
+    assert_eq!(key.field1(), None);
+    assert_eq!(key.field2(), None);
     assert_eq!(
-      format!("{:#?}", MyPrefixSeq::new()),
-      "KeyPart1[10, 20]\n  └ KeyPart2[30, 40]\n    └ KeyPart3[50, 60]",
+      layout.parse(key.as_ref()).unwrap_err(),
+      Error::TooShort {
+        len: 3,
+        min: 2 + MyLayout::KEY_LEN,
+      },
     );
+
+    let key = layout.create_key([30, 31]);
+
+    assert_eq!(key.field1(), Some(&[30, 31][..]));
+    assert_eq!(key.field2(), None);
   }
 
   #[test]
-  fn key_seq_extend() {
+  fn key_layout_parse_errors_test() {
     define_key_part!(KeyPart1, &[10, 20]);
-    define_key_part!(KeyPart2, &[30, 40]);
-    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+    define_key_layout!(MyLayout, MyLayoutFields, [KeyPart1], { field1: 2 });
 
-    let key_seq = MyPrefixSeq::new()
-      .extend("ExtensionPart1", &[50, 60])
-      .extend("ExtensionPart2", &[70, 80]);
+    let layout = MyLayout::new();
 
     assert_eq!(
-      format!("{:#?}", key_seq),
-      "KeyPart1[10, 20]\n  └ KeyPart2[30, 40]\n    └ ExtensionPart1[50, 60]\n      └ ExtensionPart2[70, 80]",
+      layout.parse([10, 20, 30, 40]).unwrap().field1(),
+      Some(&[30, 40][..]),
     );
+    assert_eq!(
+      layout.parse([10, 20, 30]).unwrap_err(),
+      Error::TooShort { len: 3, min: 4 },
+    );
+    assert_eq!(
+      layout.parse([10, 20, 30, 40, 50]).unwrap_err(),
+      Error::KeyTooLong { len: 3, max: 2 },
+    );
+    assert_eq!(
+      layout.parse([10, 21, 30, 40]).unwrap_err(),
+      Error::PrefixMismatch {
+        expected: vec![10, 20],
+        found: vec![10, 21],
+      },
+    );
+  }
 
-    let key = key_seq.create_key(&[90, 100]);
-
-    assert_eq!(key.as_ref(), &[10, 20, 30, 40, 50, 60, 70, 80, 90, 100],);
+  #[test]
+  fn error_display_test() {
+    let error: Box<dyn std::error::Error> = Box::new(Error::PrefixMismatch {
+      expected: vec![10, 20],
+      found: vec![10, 30],
+    });
 
     assert_eq!(
-      format!("{:?}", key),
-      "KeyPart1[10, 20] -> KeyPart2[30, 40] -> ExtensionPart1[50, 60] -> ExtensionPart2[70, 80] -> Key=[90, 100]",
+      error.to_string(),
+      "prefix mismatch: expected [10, 20], found [10, 30]",
     );
+    assert_eq!(
+      Error::TooShort { len: 1, min: 2 }.to_string(),
+      "bytes are too short: 1 bytes, at least 2 expected",
+    );
+    assert_eq!(
+      Error::KeyTooLong { len: 3, max: 2 }.to_string(),
+      "key is too long: 3 bytes, at most 2 allowed",
+    );
+    assert_eq!(
+      Error::LengthMismatch {
+        len: 3,
+        expected: 2
+      }
+      .to_string(),
+      "length mismatch: 3 bytes, exactly 2 expected",
+    );
+    assert_eq!(
+      Error::EmptyKey.to_string(),
+      "key is empty while the key sequence requires a key",
+    );
+  }
+
+  #[test]
+  fn create_key_encoded_test() {
+    define_key_part!(Users, &[10, 20]);
+    define_key_seq!(UsersSeq, [Users]);
+
+    let key_seq = UsersSeq::new();
+    let key = key_seq.create_key_encoded((-1i16, true, "a\0b"));
+
+    assert_eq!(key.get_prefix(), &[10, 20]);
+    assert_eq!(key.get_key(), &[0x7F, 0xFF, 1, b'a', 0, 0xFF, b'b', 0, 0]);
+
+    let key = key.to_vec();
+
+    assert_eq!(key.capacity(), key.len());
+  }
+
+  #[test]
+  fn ordered_encode_order_test() {
+    let mut values: Vec<(i32, String, u8)> = vec![
+      (-5, "b".to_string(), 1),
+      (3, "".to_string(), 9),
+      (-5, "ab".to_string(), 0),
+      (i32::MIN, "z".to_string(), 0),
+      (3, "\0".to_string(), 0),
+      (-5, "a".to_string(), 200),
+      (i32::MAX, "a".to_string(), 0),
+      (0, "a\0".to_string(), 1),
+      (0, "a".to_string(), 2),
+      (-5, "a".to_string(), 7),
+    ];
+
+    let mut encoded: Vec<Vec<u8>> = values
+      .iter()
+      .map(|value| {
+        let mut buf = Vec::new();
+        value.encode_ordered(&mut buf);
+        assert_eq!(buf.len(), value.encoded_len());
+        buf
+      })
+      .collect();
+
+    values.sort();
+    encoded.sort();
+
+    let sorted: Vec<Vec<u8>> = values
+      .iter()
+      .map(|value| {
+        let mut buf = Vec::new();
+        value.encode_ordered(&mut buf);
+        buf
+      })
+      .collect();
+
+    assert_eq!(encoded, sorted);
   }
 
   // Benches
@@ -514,6 +4555,56 @@ mod tests {
     })
   }
 
+  #[bench]
+  fn bench_write_prefix_static(b: &mut Bencher) {
+    define_key_part!(KeyPart1, "key_part_1".as_bytes());
+    define_key_part!(KeyPart2, "key_part_2".as_bytes());
+    define_key_part!(KeyPart3, "key_part_3".as_bytes());
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2, KeyPart3], sep = &[0]);
+
+    let seq = &MyPrefixSeq::new();
+
+    b.iter(|| {
+      let mut buf = Vec::with_capacity(seq.get_prefix_len());
+      seq.write_prefix(|bytes| buf.extend_from_slice(bytes));
+
+      buf
+    })
+  }
+
+  #[bench]
+  fn bench_write_prefix_per_part(b: &mut Bencher) {
+    define_key_part!(KeyPart1, "key_part_1".as_bytes());
+    define_key_part!(KeyPart2, "key_part_2".as_bytes());
+    define_key_part!(KeyPart3, "key_part_3".as_bytes());
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2, KeyPart3], sep = &[0]);
+
+    let seq = &MyPrefixSeq::new();
+
+    b.iter(|| {
+      let mut buf = Vec::with_capacity(seq.get_prefix_len());
+      seq.parts.iter().for_each(|(_, bytes)| {
+        buf.extend_from_slice(bytes);
+        buf.extend_from_slice(MyPrefixSeq::SEPARATOR);
+      });
+
+      buf
+    })
+  }
+
+  #[bench]
+  fn bench_create_key_ref(b: &mut Bencher) {
+    define_key_part!(KeyPart1, "key_part_1".as_bytes());
+    define_key_part!(KeyPart2, "key_part_2".as_bytes());
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let seq = &MyPrefixSeq::new();
+
+    b.iter(|| {
+      seq.create_key_ref("some_key".as_bytes());
+    })
+  }
+
   #[bench]
   fn bench_create_key_with_extending(b: &mut Bencher) {
     define_key_part!(KeyPart1, "key_part_1".as_bytes());
@@ -525,4 +4616,20 @@ mod tests {
       seq.create_key("some_key".as_bytes());
     })
   }
+
+  #[bench]
+  fn bench_concat_keys(b: &mut Bencher) {
+    define_key_part!(KeyPart1, "key_part_1".as_bytes());
+    define_key_part!(KeyPart2, "key_part_2".as_bytes());
+    define_key_seq!(MyPrefixSeq, [KeyPart1, KeyPart2]);
+
+    let seq = MyPrefixSeq::new();
+    let keys = [
+      seq.create_key("some_key_1".as_bytes()),
+      seq.create_key("some_key_2".as_bytes()),
+      seq.create_key("some_key_3".as_bytes()),
+    ];
+
+    b.iter(|| concat_keys(&keys))
+  }
 }
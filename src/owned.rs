@@ -0,0 +1,105 @@
+use super::formatting::{format_struct, BytesFormat};
+use super::{Key, KeyExtensionsItem, KeyPartsSequence};
+
+/// Self-contained key which keeps the key sequence structure and extensions,
+/// so it renders the same `Debug` output without the key sequence in scope
+#[derive(Clone)]
+pub struct OwnedKey {
+  bytes: Vec<u8>,
+  key_len: usize,
+  has_key: bool,
+  parts: Vec<KeyExtensionsItem>,
+  extensions: Vec<KeyExtensionsItem>,
+  separator: &'static [u8],
+}
+
+impl OwnedKey {
+  /// Returns key bytes
+  pub fn get_key(&self) -> &[u8] {
+    &self.bytes[self.bytes.len() - self.key_len..]
+  }
+
+  /// Returns prefix bytes
+  pub fn get_prefix(&self) -> &[u8] {
+    &self.bytes[..self.bytes.len() - self.key_len]
+  }
+
+  /// Moves out key bytes
+  pub fn to_vec(self) -> Vec<u8> {
+    self.bytes
+  }
+}
+
+#[cfg(not(feature = "allocator_api"))]
+impl<'a, T: KeyPartsSequence> From<Key<'a, T>> for OwnedKey {
+  fn from(key: Key<'a, T>) -> Self {
+    let parts = key
+      .layout_parts()
+      .into_iter()
+      .map(|(name, bytes)| (name, bytes.to_vec()))
+      .collect();
+
+    Self {
+      bytes: key.bytes,
+      key_len: key.key_len,
+      has_key: key.has_key,
+      parts,
+      extensions: key.extensions.map(<[_]>::to_vec).unwrap_or_default(),
+      separator: T::get_separator(),
+    }
+  }
+}
+
+/// Copies the key bytes into the global allocator
+#[cfg(feature = "allocator_api")]
+impl<'a, T: KeyPartsSequence, A: std::alloc::Allocator> From<Key<'a, T, A>> for OwnedKey {
+  fn from(key: Key<'a, T, A>) -> Self {
+    let parts = key
+      .layout_parts()
+      .into_iter()
+      .map(|(name, bytes)| (name, bytes.to_vec()))
+      .collect();
+
+    Self {
+      bytes: key.bytes.to_vec(),
+      key_len: key.key_len,
+      has_key: key.has_key,
+      parts,
+      extensions: key.extensions.map(<[_]>::to_vec).unwrap_or_default(),
+      separator: T::get_separator(),
+    }
+  }
+}
+
+impl From<OwnedKey> for Vec<u8> {
+  fn from(key: OwnedKey) -> Self {
+    key.to_vec()
+  }
+}
+
+impl std::fmt::Debug for OwnedKey {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let parts: Vec<_> = self
+      .parts
+      .iter()
+      .map(|(name, bytes)| (*name, bytes.as_slice()))
+      .collect();
+
+    format_struct(
+      &parts,
+      Some(&self.extensions),
+      self.separator,
+      self
+        .has_key
+        .then_some((self.bytes.as_slice(), self.bytes.len())),
+      BytesFormat::Decimal,
+      f,
+    )
+  }
+}
+
+impl AsRef<[u8]> for OwnedKey {
+  fn as_ref(&self) -> &[u8] {
+    self.bytes.as_slice()
+  }
+}
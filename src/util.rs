@@ -0,0 +1,24 @@
+//! Byte helpers used by the key sequences which are useful on their own
+
+/// Returns the smallest byte string greater than every byte string starting with `prefix`:
+/// the prefix with trailing `0xFF` bytes dropped and the last byte incremented.
+/// Returns `None` if the prefix is empty or consists of `0xFF` bytes only, so there is no such byte string
+///
+/// # Example
+/// ```
+/// use the_key::util::next_prefix;
+///
+/// fn main() {
+///   assert_eq!(next_prefix(&[10, 20]), Some(vec![10, 21]));
+///   assert_eq!(next_prefix(&[10, 0xFF]), Some(vec![11]));
+///   assert_eq!(next_prefix(&[0xFF]), None);
+/// }
+/// ```
+pub fn next_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+  let last = prefix.iter().rposition(|byte| *byte < 0xFF)?;
+
+  let mut next = prefix[..=last].to_vec();
+  next[last] += 1;
+
+  Some(next)
+}
@@ -0,0 +1,99 @@
+//! Derive macros for [the-key](https://docs.rs/the-key). Use them through the `derive` feature of `the-key`
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields};
+
+/// Implements `the_key::KeyPart` for a unit struct. Bytes of the key part are set by
+/// `#[key_part(bytes = ...)]` attribute and optional description by `#[key_part(desc = ...)]` attribute
+#[proc_macro_derive(KeyPart, attributes(key_part))]
+pub fn derive_key_part(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  match expand_key_part(input) {
+    Ok(tokens) => tokens.into(),
+    Err(error) => error.to_compile_error().into(),
+  }
+}
+
+fn expand_key_part(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+  let name = &input.ident;
+
+  match &input.data {
+    Data::Struct(data) if matches!(data.fields, Fields::Unit) => {}
+    _ => {
+      return Err(syn::Error::new_spanned(
+        name,
+        "KeyPart can be derived only for unit structs",
+      ))
+    }
+  }
+
+  let mut bytes: Option<Expr> = None;
+  let mut desc: Option<Expr> = None;
+
+  let attrs = input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("key_part"));
+
+  for attr in attrs {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("bytes") {
+        bytes = Some(meta.value()?.parse()?);
+
+        Ok(())
+      } else if meta.path.is_ident("desc") {
+        desc = Some(meta.value()?.parse()?);
+
+        Ok(())
+      } else {
+        Err(meta.error("unsupported key_part attribute"))
+      }
+    })?;
+  }
+
+  let bytes = bytes.ok_or_else(|| {
+    syn::Error::new_spanned(
+      name,
+      "missing #[key_part(bytes = ...)] attribute of the key part",
+    )
+  })?;
+
+  let desc = match desc {
+    Some(desc) => quote! { Some(#desc) },
+    None => quote! { None },
+  };
+
+  Ok(quote! {
+    impl #name {
+      /// Key part bytes
+      pub const BYTES: &'static [u8] = #bytes;
+
+      /// Key part description
+      pub const DESCRIPTION: Option<&'static str> = #desc;
+
+      pub const fn new() -> Self {
+        #name
+      }
+    }
+
+    impl ::the_key::KeyPart for #name {
+      fn new() -> Self {
+        #name
+      }
+
+      fn get_name(&self) -> &'static str {
+        stringify!(#name)
+      }
+
+      fn get_bytes(&self) -> &'static [u8] {
+        Self::BYTES
+      }
+
+      fn get_description(&self) -> Option<&'static str> {
+        Self::DESCRIPTION
+      }
+    }
+  })
+}